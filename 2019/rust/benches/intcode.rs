@@ -0,0 +1,59 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use nickwb_advent_2019::intcode::*;
+
+type Decoder = fn(&[MemoryCell], MemoryPointer) -> IntCodeResult<Operation>;
+
+const DECODERS: [(&str, Decoder); 2] = [("formatted", decode_formatted), ("arithmetic", decode)];
+
+// Decode every address in the program which holds something that looks like an instruction
+fn decode_everything(program: &[MemoryCell], decoder: Decoder) -> usize {
+    (0..program.len())
+        .filter(|&at| (0..100000).contains(&program[at]))
+        .filter(|&at| decoder(program, at).is_ok())
+        .count()
+}
+
+fn bench_decoders(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode");
+    for day in [7, 9, 15, 17] {
+        let program = load_program(&format!("inputs/day{}.txt", day));
+        for (name, decoder) in DECODERS.iter() {
+            group.bench_with_input(BenchmarkId::new(*name, day), &program, |b, p| {
+                b.iter(|| decode_everything(p, *decoder))
+            });
+        }
+    }
+    group.finish();
+}
+
+fn run_program(program: &[MemoryCell], inputs: &[MemoryCell], use_cache: bool) -> MemoryCell {
+    let mut computer = Computer::new(
+        PagedStorage::from(program),
+        BufferInput::new(inputs.len()),
+        BufferOutput::new(1),
+    );
+    if use_cache {
+        computer.enable_decode_cache();
+    }
+    computer.input().queue_many(inputs);
+    computer.run_until_halt().unwrap();
+    computer.output().last().unwrap()
+}
+
+fn bench_execution(c: &mut Criterion) {
+    let mut group = c.benchmark_group("execute");
+    let runs: [(&str, Vec<MemoryCell>, Vec<MemoryCell>); 2] = [
+        ("day7", load_program("inputs/day7.txt"), vec![4, 0]),
+        ("day9", load_program("inputs/day9.txt"), vec![1]),
+    ];
+    for (name, program, inputs) in runs.iter() {
+        for use_cache in [false, true] {
+            let id = BenchmarkId::new(*name, if use_cache { "cached" } else { "uncached" });
+            group.bench_function(id, |b| b.iter(|| run_program(program, inputs, use_cache)));
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_decoders, bench_execution);
+criterion_main!(benches);
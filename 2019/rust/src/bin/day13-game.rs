@@ -1,8 +1,24 @@
 use std::io::Write;
 
-use nickwb_advent_2019::day13::GameState;
+use nickwb_advent_2019::day13::{self, GameState};
+use nickwb_advent_2019::intcode::Recording;
+
+const USAGE: &str = "Usage: day13-game [record <path> | replay <path>]";
 
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let record_to = match args.iter().map(|a| a.as_str()).collect::<Vec<_>>()[..] {
+        [] => None,
+        ["record", path] => Some(path.to_string()),
+        ["replay", path] => {
+            let recording = Recording::load(path).expect("Could not load the recording");
+            day13::replay(recording);
+            println!("The recording replayed exactly");
+            return;
+        }
+        _ => panic!("{}", USAGE),
+    };
+
     let mut state = GameState::new();
     let mut move_input = String::with_capacity(5);
     let mut is_cheating = false;
@@ -29,4 +45,12 @@ fn main() {
         move_input.clear();
         println!("");
     }
+
+    if let Some(path) = record_to {
+        state
+            .recording()
+            .save(&path)
+            .expect("Could not save the recording");
+        println!("Saved the recording to {}", path);
+    }
 }
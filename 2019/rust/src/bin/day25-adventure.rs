@@ -0,0 +1,40 @@
+use std::io::Write;
+
+use nickwb_advent_2019::day25::{self, Adventure, Terminal};
+
+const USAGE: &str = "Usage: day25-adventure [auto]";
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let is_auto = match args.iter().map(|a| a.as_str()).collect::<Vec<_>>()[..] {
+        [] => false,
+        ["auto"] => true,
+        _ => panic!("{}", USAGE),
+    };
+
+    let mut adventure = Adventure::load().expect("Could not load the adventure");
+    if is_auto {
+        match day25::solve(&mut adventure) {
+            Some(password) => println!("The airlock password is {}", password),
+            None => println!("Couldn't find a way through the checkpoint"),
+        }
+        return;
+    }
+
+    let mut command = String::new();
+    loop {
+        print!("{}", adventure.read());
+        if adventure.has_ended() {
+            break;
+        }
+
+        std::io::stdout().lock().flush().unwrap();
+        command.clear();
+        if std::io::stdin().read_line(&mut command).unwrap() == 0 {
+            break;
+        }
+        if adventure.try_write(command.trim()).is_err() {
+            println!("The droid only understands plain ASCII, try that again");
+        }
+    }
+}
@@ -0,0 +1,17 @@
+use nickwb_advent_2019::intcode;
+
+fn main() {
+    let path = std::env::args()
+        .nth(1)
+        .expect("Usage: intcode-cfg <path to program>");
+    let program = intcode::load_program(&path);
+    let graph = intcode::analyse(&program);
+
+    for modification in graph.self_modifications() {
+        eprintln!(
+            "Instruction at {} writes to code at {}",
+            modification.address, modification.target
+        );
+    }
+    print!("{}", graph.to_dot());
+}
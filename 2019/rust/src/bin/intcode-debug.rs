@@ -0,0 +1,178 @@
+use std::io::Write;
+
+use nickwb_advent_2019::intcode::*;
+
+type DebugComputer = Computer<PagedStorage, BufferInput, BufferOutput>;
+
+const HELP: &str = "Commands:
+  s [n]          Step n instructions (default 1)
+  c              Continue until halt, input, breakpoint or watchpoint
+  b <addr>       Toggle a breakpoint
+  w <addr>       Toggle a watchpoint
+  m <addr> [n]   Dump n memory cells (default 8)
+  l [addr] [n]   Disassemble n instructions (default from the program counter)
+  i <v> [v..]    Queue input values
+  r              Show the registers
+  q              Quit";
+
+fn show_current(computer: &DebugComputer) {
+    let program = computer.dump_memory();
+    print!(
+        "{}",
+        disassemble_from(&program, computer.program_counter(), 1)
+    );
+}
+
+fn show_outputs(computer: &mut DebugComputer) {
+    for value in computer.output().pop_all() {
+        println!("Output: {}", value);
+    }
+}
+
+fn report(computer: &mut DebugComputer, result: IntCodeResult<StepResult>) {
+    show_outputs(computer);
+    match result {
+        Ok(StepResult::Continue) => (),
+        Ok(StepResult::Halt) => println!("Halted"),
+        Ok(StepResult::WaitingOnInput) => println!("Waiting on input"),
+        Ok(StepResult::Breakpoint(addr)) => println!("Breakpoint at {}", addr),
+        Ok(StepResult::Watchpoint(addr)) => println!("Watchpoint: wrote to {}", addr),
+        Ok(StepResult::BudgetExhausted) => println!("Instruction budget exhausted"),
+        Err(e) => println!("Error: {}", e),
+    }
+    if !computer.has_halted() {
+        show_current(computer);
+    }
+}
+
+fn parse_args(args: &[&str]) -> Option<Vec<MemoryCell>> {
+    args.iter().map(|a| a.parse::<MemoryCell>().ok()).collect()
+}
+
+fn run_command(computer: &mut DebugComputer, command: &str, args: &[MemoryCell]) -> bool {
+    // Everything except input is an address or a count
+    let pointers: Option<Vec<MemoryPointer>> = args
+        .iter()
+        .map(|&v| MemoryPointer::try_from(v).ok())
+        .collect();
+    if command != "i" && pointers.is_none() {
+        println!("Addresses and counts can't be negative");
+        return true;
+    }
+    let pointer = |idx: usize| pointers.as_ref().and_then(|p| p.get(idx).copied());
+
+    match (command, args.len()) {
+        ("s", 0) | ("s", 1) => {
+            let count = pointer(0).unwrap_or(1);
+            let mut result = Ok(StepResult::Continue);
+            for _ in 0..count {
+                result = computer.step();
+                match result {
+                    Ok(StepResult::Continue) => (),
+                    _ => break,
+                }
+            }
+            report(computer, result);
+        }
+        ("c", 0) => {
+            let result = computer.resume();
+            report(computer, result);
+        }
+        ("b", 1) => {
+            let addr = pointer(0).unwrap();
+            if computer.add_breakpoint(addr) {
+                println!("Added breakpoint at {}", addr);
+            } else {
+                computer.remove_breakpoint(addr);
+                println!("Removed breakpoint at {}", addr);
+            }
+        }
+        ("w", 1) => {
+            let addr = pointer(0).unwrap();
+            if computer.add_watchpoint(addr) {
+                println!("Added watchpoint on {}", addr);
+            } else {
+                computer.remove_watchpoint(addr);
+                println!("Removed watchpoint on {}", addr);
+            }
+        }
+        ("m", 1) | ("m", 2) => {
+            let from = pointer(0).unwrap();
+            let count = pointer(1).unwrap_or(8);
+            for addr in from..from.saturating_add(count) {
+                match computer.read_memory(addr) {
+                    Ok(value) => println!("{:>6}: {}", addr, value),
+                    Err(e) => {
+                        println!("{:>6}: {}", addr, e);
+                        break;
+                    }
+                }
+            }
+        }
+        ("l", 0..=2) => {
+            let from = pointer(0).unwrap_or(computer.program_counter());
+            let count = pointer(1).unwrap_or(10);
+            let program = computer.dump_memory();
+            print!("{}", disassemble_from(&program, from, count));
+        }
+        ("i", n) if n > 0 => computer.input().queue_many(args),
+        ("r", 0) => {
+            println!("Program counter: {}", computer.program_counter());
+            println!("Relative base:   {}", computer.relative_base());
+            println!("Instructions:    {}", computer.instruction_count());
+            println!(
+                "Memory:          {} pages of {} cells",
+                computer.storage().pages_allocated(),
+                PAGE_SIZE
+            );
+            println!("Breakpoints:     {:?}", computer.breakpoints());
+            println!("Watchpoints:     {:?}", computer.watchpoints());
+        }
+        ("q", 0) => return false,
+        _ => println!("{}", HELP),
+    }
+
+    true
+}
+
+fn main() {
+    let path = std::env::args()
+        .nth(1)
+        .expect("Usage: intcode-debug <path to program>");
+    let mut computer = Computer::new(
+        PagedStorage::from(load_program(&path)),
+        BufferInput::new(16),
+        BufferOutput::new(16),
+    );
+
+    println!("{}", HELP);
+    show_current(&computer);
+
+    let mut line = String::new();
+    loop {
+        print!("> ");
+        std::io::stdout().lock().flush().unwrap();
+        line.clear();
+        if std::io::stdin().read_line(&mut line).unwrap() == 0 {
+            break;
+        }
+
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let (command, args) = match words.split_first() {
+            Some((c, a)) => (*c, a),
+            None => continue,
+        };
+
+        let keep_going = match parse_args(args) {
+            Some(args) => run_command(&mut computer, command, &args),
+            None => {
+                println!("Arguments must be numbers");
+                true
+            }
+        };
+
+        if !keep_going {
+            break;
+        }
+    }
+}
@@ -0,0 +1,9 @@
+use nickwb_advent_2019::intcode;
+
+fn main() {
+    let path = std::env::args()
+        .nth(1)
+        .expect("Usage: intcode-disasm <path to program>");
+    let program = intcode::load_program(&path);
+    print!("{}", intcode::disassemble(&program));
+}
@@ -1,3 +1,4 @@
+use crate::solution::{self, Skipped, Solution};
 use crate::util;
 
 fn get_fuel(mass: f64) -> f64 {
@@ -19,17 +20,31 @@ fn input() -> Vec<f64> {
     util::read_int_array("inputs/day1.txt")
 }
 
-fn calculate_day_one() -> (f64, f64) {
-    let input = input();
-    let part_one: f64 = input.iter().copied().map(|i| get_fuel(i)).sum();
-    let part_two: f64 = input.iter().copied().map(|i| get_total_fuel(i)).sum();
-    (part_one, part_two)
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2019;
+    const DAY: u32 = 1;
+
+    type Input = Vec<f64>;
+    type PartOne = f64;
+    type PartTwo = f64;
+
+    fn parse() -> Result<Vec<f64>, Skipped> {
+        Ok(input())
+    }
+
+    fn part_one(input: &Vec<f64>) -> f64 {
+        input.iter().copied().map(|i| get_fuel(i)).sum()
+    }
+
+    fn part_two(input: &Vec<f64>) -> f64 {
+        input.iter().copied().map(|i| get_total_fuel(i)).sum()
+    }
 }
 
 pub fn run_day_one() {
-    let (part_one, part_two) = calculate_day_one();
-    println!("Day 1, Part 1: {}", part_one);
-    println!("Day 1, Part 2: {}", part_two);
+    solution::run::<Solver>();
 }
 
 #[test]
@@ -69,7 +84,7 @@ fn example_7() {
 
 #[test]
 fn actual_day_1() {
-    let (part_one, part_two) = calculate_day_one();
+    let (part_one, part_two) = solution::answers::<Solver>();
     assert_eq!(3412207f64, part_one);
     assert_eq!(5115436f64, part_two);
 }
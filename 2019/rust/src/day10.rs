@@ -1,3 +1,4 @@
+use crate::solution::{self, Skipped, Solution};
 use crate::util::{self, CoordinateMapping, Direction};
 use num::integer::gcd;
 use rayon::prelude::*;
@@ -9,7 +10,7 @@ type Dimension = i32;
 type Point = crate::util::Point<Dimension>;
 
 #[derive(Debug, PartialEq, Clone)]
-struct Map {
+pub struct Map {
     width: Dimension,
     height: Dimension,
     asteroids: HashSet<Point>,
@@ -237,17 +238,34 @@ fn part_two(map: &mut Map, best_point: Point) -> Point {
     order[199]
 }
 
-fn calculate_day_ten() -> (Point, usize, Point) {
-    let mut map = input();
-    let (best, observed) = part_one(&map);
-    let two_hundred = part_two(&mut map, best);
-    (best, observed, two_hundred)
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2019;
+    const DAY: u32 = 10;
+
+    type Input = Map;
+    type PartOne = String;
+    type PartTwo = String;
+
+    fn parse() -> Result<Map, Skipped> {
+        Ok(input())
+    }
+
+    fn part_one(input: &Map) -> String {
+        let (best, observed) = part_one(input);
+        format!("{} at ({}, {})", observed, best.x, best.y)
+    }
+
+    fn part_two(input: &Map) -> String {
+        let (best, _) = part_one(input);
+        let two_hundred = part_two(&mut input.clone(), best);
+        format!("({}, {})", two_hundred.x, two_hundred.y)
+    }
 }
 
 pub fn run_day_ten() {
-    let (best, observed, two_hundred) = calculate_day_ten();
-    println!("Day 10, Part 1: {} at ({}, {})", observed, best.x, best.y);
-    println!("Day 10, Part 2: ({}, {})", two_hundred.x, two_hundred.y);
+    solution::run::<Solver>();
 }
 
 #[test]
@@ -364,7 +382,9 @@ fn example_5() {
 
 #[test]
 fn actual_part_1_and_2() {
-    let (best, observed, two_hundred) = calculate_day_ten();
+    let mut map = input();
+    let (best, observed) = part_one(&map);
+    let two_hundred = part_two(&mut map, best);
     assert_eq!(Point::xy(27, 19), best);
     assert_eq!(314, observed);
     assert_eq!(Point::xy(15, 13), two_hundred);
@@ -1,4 +1,5 @@
 use crate::intcode::*;
+use crate::solution::{self, Skipped, Solution};
 use crate::util::{CoordinateMapping, Direction};
 use std::cell::RefCell;
 use std::cmp::{max, min};
@@ -94,22 +95,21 @@ fn inputs() -> Vec<MemoryCell> {
     crate::util::read_int_array("inputs/day11.txt")
 }
 
-fn run_robot_until_completion(start_color: Colour) -> PaintingRobot {
+fn run_robot_until_completion(program: &[MemoryCell], start_color: Colour) -> PaintingRobot {
     let cell = RefCell::new(PaintingRobot::new(start_color));
     let input = RobotInput { robot: &cell };
     let output = RobotOutput {
         robot: &cell,
         instruction: 0,
     };
-    let mut computer = Computer::new(inputs(), input, output);
-    computer.enable_extra_memory();
+    let mut computer = Computer::new(PagedStorage::from(program), input, output);
     computer.run_until_halt().unwrap();
 
     cell.into_inner()
 }
 
-fn calculate_part_1() -> usize {
-    let robot = run_robot_until_completion(Colour::Black);
+fn calculate_part_1(program: &[MemoryCell]) -> usize {
+    let robot = run_robot_until_completion(program, Colour::Black);
     robot.colours.len()
 }
 
@@ -127,31 +127,54 @@ fn find_bounds(robot: &PaintingRobot) -> (Coordinate, Coordinate, Coordinate, Co
         })
 }
 
-fn render_part_2() {
-    let robot = run_robot_until_completion(Colour::White);
+fn render_part_2(program: &[MemoryCell]) -> String {
+    let robot = run_robot_until_completion(program, Colour::White);
+    let mut picture = String::new();
     let (min_x, max_x, min_y, max_y) = find_bounds(&robot);
     for i in (min_y..=max_y).rev() {
         for j in min_x..=max_x {
             match robot.get_color_at_point(&Point::xy(j, i)) {
                 Colour::Black => {
-                    print!(" ");
+                    picture.push(' ');
                 }
                 Colour::White => {
-                    print!("█");
+                    picture.push('█');
                 }
             }
         }
-        println!("");
+        picture.push('\n');
+    }
+    picture
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2019;
+    const DAY: u32 = 11;
+
+    type Input = Vec<MemoryCell>;
+    type PartOne = usize;
+    type PartTwo = String;
+
+    fn parse() -> Result<Vec<MemoryCell>, Skipped> {
+        Ok(inputs())
+    }
+
+    fn part_one(input: &Vec<MemoryCell>) -> usize {
+        calculate_part_1(input)
+    }
+
+    fn part_two(input: &Vec<MemoryCell>) -> String {
+        render_part_2(input)
     }
 }
 
 pub fn run_day_eleven() {
-    println!("Day 11, Part 1: {}", calculate_part_1());
-    println!("Day 11, Part 2:");
-    render_part_2();
+    solution::run::<Solver>();
 }
 
 #[test]
 fn actual_day_11() {
-    assert_eq!(2339, calculate_part_1());
+    assert_eq!(2339, calculate_part_1(&inputs()));
 }
@@ -1,3 +1,4 @@
+use crate::solution::{self, Skipped, Solution};
 use num::integer::lcm;
 use regex::Regex;
 use std::collections::HashSet;
@@ -5,13 +6,30 @@ use std::collections::HashSet;
 type BaseInt = i32;
 
 pub fn run_day_twelve() {
-    let mut moons_one = inputs();
-    let mut moons_two = moons_one.clone();
-    println!(
-        "Day 12, Part 1: {}",
-        calculate_part_one(&mut moons_one, 1000)
-    );
-    println!("Day 12, Part 2: {}", calculate_part_two(&mut moons_two));
+    solution::run::<Solver>();
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2019;
+    const DAY: u32 = 12;
+
+    type Input = MoonSet;
+    type PartOne = BaseInt;
+    type PartTwo = u64;
+
+    fn parse() -> Result<MoonSet, Skipped> {
+        Ok(inputs())
+    }
+
+    fn part_one(input: &MoonSet) -> BaseInt {
+        calculate_part_one(&mut input.clone(), 1000)
+    }
+
+    fn part_two(input: &MoonSet) -> u64 {
+        calculate_part_two(&mut input.clone())
+    }
 }
 
 fn calculate_part_one(set: &mut MoonSet, total_steps: u64) -> BaseInt {
@@ -102,8 +120,8 @@ const ZERO_MOON: Moon = Moon {
 };
 
 #[derive(Debug, Clone, PartialEq)]
-struct MoonSet {
-    pub moons: [Moon; 4],
+pub struct MoonSet {
+    moons: [Moon; 4],
 }
 
 impl MoonSet {
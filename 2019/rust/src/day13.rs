@@ -1,4 +1,5 @@
 use crate::intcode::*;
+use crate::solution::{self, NoPartTwo, Skipped, Solution};
 use std::collections::HashMap;
 
 type Point = crate::util::Point<MemoryCell>;
@@ -105,20 +106,22 @@ impl OutputSink for ScreenBuffer {
     }
 }
 
+type GameComputer = Computer<PagedStorage, BufferInput, ScreenBuffer, Recorder>;
+
 pub struct GameState {
-    comp: Computer<Vec<MemoryCell>, BufferInput, ScreenBuffer>,
+    comp: GameComputer,
     paddle_at: Point,
     ball_at: Point,
 }
 
 impl GameState {
     pub fn new() -> GameState {
-        let input = BufferInput::new(1);
-        let output = ScreenBuffer::new();
-        let mut state = inputs();
-        state[0] = 2;
-        let mut computer = Computer::new(state, input, output);
-        computer.enable_extra_memory();
+        let computer = Computer::new(
+            PagedStorage::from(free_play()),
+            BufferInput::new(1),
+            ScreenBuffer::new(),
+        )
+        .with_tracer(Recorder::new());
 
         GameState {
             comp: computer,
@@ -131,7 +134,8 @@ impl GameState {
         let game_over = match self.comp.resume() {
             Ok(StepResult::WaitingOnInput) => false,
             Ok(StepResult::Halt) => true,
-            _ => panic!("The computer got in to an invalid state"),
+            Ok(other) => panic!("The computer stopped unexpectedly: {:?}", other),
+            Err(e) => panic!("The computer got in to an invalid state: {}", e),
         };
 
         let (paddle_at, ball_at) = self.comp.output().render_frame();
@@ -168,17 +172,49 @@ impl GameState {
             self.buffer_neutral();
         }
     }
+
+    // Every joystick move and screen update so far
+    pub fn recording(&mut self) -> Recording {
+        self.comp.tracer().recording()
+    }
 }
 
 fn inputs() -> Vec<MemoryCell> {
     crate::util::read_int_array("inputs/day13.txt")
 }
 
-fn calculate_part_one() -> usize {
+// Playing for free means we don't need to insert any quarters
+fn free_play() -> Vec<MemoryCell> {
+    let mut state = inputs();
+    state[0] = 2;
+    state
+}
+
+// Run the game against a recorded session, panicking if the screen ever differs
+pub fn replay(recording: Recording) {
+    let replayer = Replayer::new(recording);
+    let mut computer = Computer::new(
+        PagedStorage::from(free_play()),
+        replayer.input(),
+        ScreenBuffer::new(),
+    )
+    .with_tracer(replayer);
+
+    match computer.resume() {
+        Ok(StepResult::Halt) | Ok(StepResult::WaitingOnInput) => (),
+        Ok(other) => panic!("The computer stopped unexpectedly: {:?}", other),
+        Err(e) => panic!("The computer got in to an invalid state: {}", e),
+    }
+    assert!(
+        computer.tracer().is_complete(),
+        "The recording wasn't played out"
+    );
+}
+
+fn calculate_part_one(program: &[MemoryCell]) -> usize {
     let input = NoInput {};
     let output = ScreenBuffer::new();
-    let mut computer = Computer::new(inputs(), input, output);
-    computer.enable_extra_memory();
+    let mut computer = Computer::new(PagedStorage::from(program), input, output);
     computer.run_until_halt().unwrap();
 
     computer
@@ -189,12 +225,51 @@ fn calculate_part_one() -> usize {
         .count()
 }
 
+// The second part is played by hand, with the day13-game binary
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2019;
+    const DAY: u32 = 13;
+
+    type Input = Vec<MemoryCell>;
+    type PartOne = usize;
+    type PartTwo = NoPartTwo;
+
+    fn parse() -> Result<Vec<MemoryCell>, Skipped> {
+        Ok(inputs())
+    }
+
+    fn part_one(input: &Vec<MemoryCell>) -> usize {
+        calculate_part_one(input)
+    }
+
+    fn part_two(_: &Vec<MemoryCell>) -> NoPartTwo {
+        NoPartTwo
+    }
+}
+
 pub fn run_day_thirteen() {
-    println!("Day 13, Part 1: {}", calculate_part_one());
+    solution::run::<Solver>();
+}
+
+#[test]
+fn replays_a_recorded_game() {
+    let mut state = GameState::new();
+    for _ in 0..50 {
+        if state.run_one_cycle() {
+            break;
+        }
+        state.buffer_optimal();
+    }
+
+    let recording = state.recording();
+    assert!(recording.inputs().count() >= 49);
+    replay(recording);
 }
 
 #[test]
 fn actual_part_1() {
-    let result = calculate_part_one();
+    let result = calculate_part_one(&inputs());
     assert_eq!(341, result);
 }
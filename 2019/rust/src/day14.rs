@@ -1,3 +1,4 @@
+use crate::solution::{self, Skipped, Solution};
 use regex::Regex;
 use std::collections::{HashMap, VecDeque};
 
@@ -6,7 +7,7 @@ const FUEL_IDX: usize = 1;
 const PART_TWO_ORE: usize = 1_000_000_000_000;
 
 #[derive(Debug)]
-struct InputInterpretation {
+pub struct InputInterpretation {
     compount_count: usize,
     compound_map: HashMap<String, usize>,
     reactions: Vec<Reaction>,
@@ -402,10 +403,31 @@ fn inputs() -> InputInterpretation {
     InputInterpretation::parse_input(&text).unwrap()
 }
 
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2019;
+    const DAY: u32 = 14;
+
+    type Input = InputInterpretation;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse() -> Result<InputInterpretation, Skipped> {
+        Ok(inputs())
+    }
+
+    fn part_one(input: &InputInterpretation) -> usize {
+        calculate_part_1(input)
+    }
+
+    fn part_two(input: &InputInterpretation) -> usize {
+        calculate_part_2(input)
+    }
+}
+
 pub fn run_day_fourteen() {
-    let input = inputs();
-    println!("Day 14, Part 1: {}", calculate_part_1(&input));
-    println!("Day 14, Part 2: {}", calculate_part_2(&input));
+    solution::run::<Solver>();
 }
 
 #[test]
@@ -1,4 +1,5 @@
 use crate::intcode::*;
+use crate::solution::{self, Skipped, Solution};
 use std::{
     cell::RefCell,
     collections::{HashMap, HashSet, VecDeque},
@@ -6,22 +7,44 @@ use std::{
     rc::Rc,
 };
 
+// Both parts need the whole map, so the droid explores it up front
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2019;
+    const DAY: u32 = 15;
+
+    type Input = Droid;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse() -> Result<Droid, Skipped> {
+        let mut droid = Droid::new(inputs());
+        droid.explore_whole_map_bfs();
+        Ok(droid)
+    }
+
+    fn part_one(input: &Droid) -> usize {
+        input.oxygen_bfs().unwrap().0
+    }
+
+    fn part_two(input: &Droid) -> usize {
+        let (_, oxygen_location) = input.oxygen_bfs().unwrap();
+        input.flood_fill(oxygen_location)
+    }
+}
+
 pub fn run_day_fifteen() {
-    let mut droid = Droid::new(inputs());
-    droid.explore_whole_map_dfs();
-    let (depth, oxygen_location) = droid.oxygen_bfs().unwrap();
-    println!("Day 15, Part 1: {}", depth);
-    let fill_time = droid.flood_fill(oxygen_location);
-    println!("Day 15, Part 2: {}", fill_time);
+    solution::run::<Solver>();
 }
 
 fn inputs() -> Vec<MemoryCell> {
     crate::util::read_int_array("inputs/day15.txt")
 }
 
-type DroidComputer = Computer<Vec<MemoryCell>, Rc<RefCell<DroidIo>>, Rc<RefCell<DroidIo>>>;
+type DroidComputer = Computer<PagedStorage, Rc<RefCell<DroidIo>>, Rc<RefCell<DroidIo>>>;
 
-struct Droid {
+pub struct Droid {
     computer: DroidComputer,
     map: HashMap<Point, Observation>,
     location: Point,
@@ -34,8 +57,7 @@ impl Droid {
             next_output: None,
         }));
 
-        let mut computer: DroidComputer = Computer::new(program, io.clone(), io);
-        computer.enable_extra_memory();
+        let computer: DroidComputer = Computer::new(PagedStorage::from(program), io.clone(), io);
 
         Self {
             computer,
@@ -44,61 +66,35 @@ impl Droid {
         }
     }
 
-    fn explore_whole_map_dfs(&mut self) {
+    // Explore breadth-first, forking the computer from a snapshot at each location rather than
+    // walking the droid back and forth
+    fn explore_whole_map_bfs(&mut self) {
         // Mark 0,0 as explored
         self.map.insert(ZERO_POINT, Observation::Empty);
 
-        let mut candidates: Vec<Point> = Vec::new();
+        let mut candidates: VecDeque<(Point, Snapshot)> = VecDeque::new();
+        candidates.push_back((ZERO_POINT, self.computer.snapshot()));
 
-        // Initialise candidates with the neighbours of 0,0
-        for (_d, n) in Self::neighbours(&ZERO_POINT) {
-            candidates.push(n);
-        }
-
-        // Track our path so we can backtrack if required
-        let mut path: Vec<Direction> = Vec::new();
-
-        while let Some(dest) = candidates.pop() {
-            // Ignore candidates we've already explored
-            if self.map.contains_key(&dest) {
-                continue;
-            }
-
-            // Backtrack if necessary
-            let direction = loop {
-                match self.is_adjacent(&dest) {
-                    None => {
-                        let direction = path.pop().unwrap();
-                        self.traverse(match direction {
-                            Direction::North => Direction::South,
-                            Direction::South => Direction::North,
-                            Direction::East => Direction::West,
-                            Direction::West => Direction::East,
-                        });
-                    }
-                    Some(d) => break d,
-                }
-            };
-
-            // Visit the new location
-            let observation = self.traverse(direction);
-
-            self.map.insert(dest, observation);
-
-            // If we didn't just ram the wall, add this movement to the backtrack
-            if observation != Observation::Wall {
-                path.push(direction);
-            }
-
-            // Add each new neighbour as a candidate
-            for (_d, n) in Self::neighbours(&self.location) {
+        while let Some((location, snapshot)) = candidates.pop_front() {
+            for (d, n) in Self::neighbours(&location) {
                 // Ignore the neigbour if we've already seen it
                 if self.map.contains_key(&n) {
                     continue;
                 }
 
-                // Mark that cell for exploring
-                candidates.push(n);
+                // Put the droid back where it was, and try moving in this direction
+                self.computer
+                    .restore(&snapshot)
+                    .expect("Snapshot is from this computer");
+                self.location = location;
+                let observation = self.traverse(d);
+
+                self.map.insert(n, observation);
+
+                // If we didn't just ram the wall, explore onwards from here
+                if observation != Observation::Wall {
+                    candidates.push_back((n, self.computer.snapshot()));
+                }
             }
         }
     }
@@ -164,12 +160,6 @@ impl Droid {
         })
     }
 
-    fn is_adjacent(&self, location: &Point) -> Option<Direction> {
-        Self::neighbours(&self.location)
-            .filter_map(|(d, l)| if &l == location { Some(d) } else { None })
-            .next()
-    }
-
     // Find the optimal path to the oxygen from the origin,
     // returning the moves taken and the location of the oxygen
     fn oxygen_bfs(&self) -> Option<(usize, Point)> {
@@ -280,7 +270,7 @@ mod tests {
     #[test]
     fn actual_inputs() {
         let mut droid = Droid::new(inputs());
-        droid.explore_whole_map_dfs();
+        droid.explore_whole_map_bfs();
         let (depth, oxygen_location) = droid.oxygen_bfs().unwrap();
         assert_eq!(336, depth);
         let fill_time = droid.flood_fill(oxygen_location);
@@ -1,12 +1,32 @@
+use crate::solution::{self, Skipped, Solution};
 use rayon::prelude::*;
 use std::{convert::TryInto, iter::repeat};
 
 pub fn run_day_sixteen() {
-    let text = inputs();
-    let part_1 = Digits::parse(&text).calculate_fft(100).to_string(8);
-    let part_2 = Digits::parse(&text).solve_part_two();
-    println!("Day 16, Part 1: {}", part_1);
-    println!("Day 16, Part 2: {}", part_2);
+    solution::run::<Solver>();
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2019;
+    const DAY: u32 = 16;
+
+    type Input = String;
+    type PartOne = String;
+    type PartTwo = String;
+
+    fn parse() -> Result<String, Skipped> {
+        Ok(inputs())
+    }
+
+    fn part_one(input: &String) -> String {
+        Digits::parse(input).calculate_fft(100).to_string(8)
+    }
+
+    fn part_two(input: &String) -> String {
+        Digits::parse(input).solve_part_two()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -1,14 +1,35 @@
-use std::collections::HashSet;
-
-use crate::intcode::{Computer, MemoryCell, NoInput, OutputSink, StepResult};
+use crate::intcode::{
+    AsciiInput, AsciiOutput, Computer, MemoryCell, NoInput, PagedStorage, StepResult,
+};
+use crate::solution::{self, Skipped, Solution};
 
 mod path_find;
 
 pub fn run_day_seventeen() {
-    let part_1 = solve_first();
+    solution::run::<Solver>();
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2019;
+    const DAY: u32 = 17;
+
+    type Input = Vec<MemoryCell>;
+    type PartOne = usize;
+    type PartTwo = MemoryCell;
+
+    fn parse() -> Result<Vec<MemoryCell>, Skipped> {
+        Ok(inputs())
+    }
+
+    fn part_one(input: &Vec<MemoryCell>) -> usize {
+        solve_first(input)
+    }
 
-    println!("Day 17, Part 1: {}", part_1);
-    println!("Day 17, Part 2: {}", 0);
+    fn part_two(input: &Vec<MemoryCell>) -> MemoryCell {
+        solve_second(input)
+    }
 }
 
 // 4,L,4,L,4,L,4,L,4,L,12
@@ -18,17 +39,19 @@ pub fn run_day_seventeen() {
 // R,10,L,12,R,6, R,10,L,12,R,6
 // A,A,A,A,A,A,A,A
 
-fn solve_first() -> usize {
-    let program = inputs();
-    let mut computer = Computer::new(program, NoInput, CameraBuffer::default());
-    computer.enable_extra_memory();
+fn read_camera(program: &[MemoryCell]) -> CameraBuffer {
+    let mut computer = Computer::new(PagedStorage::from(program), NoInput, AsciiOutput::new());
 
     match computer.resume() {
         Ok(StepResult::Halt) => (),
         _ => panic!("Something didn't compute as expected"),
     };
 
-    let camera = computer.output();
+    CameraBuffer::from_text(computer.output().text())
+}
+
+fn solve_first(program: &[MemoryCell]) -> usize {
+    let camera = read_camera(program);
     eprintln!("{}", camera.to_string());
 
     let (width, height) = camera.width_and_height().expect("Can get width and height");
@@ -51,6 +74,33 @@ fn solve_first() -> usize {
     result
 }
 
+fn solve_second(program: &[MemoryCell]) -> MemoryCell {
+    let camera = read_camera(program);
+    let path = path_find::find_path(&camera).expect("Found a robot");
+    let routines = path_find::compress(&path).expect("The path fits in to three functions");
+
+    // Wake the robot up, and give it its routines. We don't need the video feed.
+    let mut program = program.to_vec();
+    program[0] = 2;
+    let mut computer = Computer::new(
+        PagedStorage::from(program),
+        AsciiInput::new(),
+        AsciiOutput::new(),
+    );
+    for line in routines.lines() {
+        computer.input().queue_line(&line);
+    }
+    computer.input().queue_line("n");
+
+    computer
+        .run_until_halt()
+        .expect("The robot ran to completion");
+    computer
+        .output()
+        .last_non_ascii()
+        .expect("The robot reported the dust it collected")
+}
+
 #[derive(Clone, Copy, PartialEq)]
 enum RobotState {
     Up,
@@ -151,11 +201,16 @@ impl Default for CameraBuffer {
     }
 }
 
-impl OutputSink for CameraBuffer {
-    fn write(&mut self, value: MemoryCell) {
-        let byte: u8 = value.try_into().expect("Should fit in a byte");
-        let symbol = byte as char;
+impl CameraBuffer {
+    fn from_text(text: &str) -> CameraBuffer {
+        let mut camera = CameraBuffer::default();
+        for symbol in text.chars() {
+            camera.push_symbol(symbol);
+        }
+        camera
+    }
 
+    fn push_symbol(&mut self, symbol: char) {
         match symbol {
             '.' => self.grid.push(Observation::Empty),
             '#' => self.grid.push(Observation::Scaffold),
@@ -194,6 +249,7 @@ mod tests {
 
     #[test]
     fn actual_inputs() {
-        assert_eq!(7816, solve_first());
+        assert_eq!(7816, solve_first(&inputs()));
+        assert_eq!(952010, solve_second(&inputs()));
     }
 }
@@ -1,255 +1,189 @@
-use std::collections::VecDeque;
+use std::fmt;
 
-use slotmap::{new_key_type, Key, SlotMap};
+use super::{CameraBuffer, RobotState};
+use crate::util::{CoordinateMapping, Direction};
 
-use super::CameraBuffer;
+type Point = crate::util::Point<isize>;
 
-fn find_path(camera: &CameraBuffer) -> usize {
-    let mut context = Context::new(camera);
-    let context = &mut context;
+// Each routine can be at most 20 characters, not counting the newline
+const MAX_ROUTINE_CHARS: usize = 20;
 
-    let mut stack: Vec<CandidateId> = Vec::new();
-    let mut next_candidates: Vec<ChildCandidateBuilder> = Vec::with_capacity(ALL_STRATEGIES.len());
-
-    // Insert the initial candidate
-    let (start_pos, start_dir) = context.get_robot().expect("Found a robot");
-    stack.push(context.insert_candidate(Candidate::initial(start_pos, start_dir)));
-
-    while let Some(candidate_id) = stack.pop() {
-        // Get the candidate
-        let candidate = context
-            .candidates
-            .get(candidate_id)
-            .expect("Found candidate");
-
-        // Check if we've reached the end
-
-        // Consider all further moves
-        next_candidates.extend(
-            ALL_STRATEGIES
-                .iter()
-                .filter_map(|s| build_child_candidate_if_possible(candidate, context, s)),
-        );
+// The absolute longest any sequence can be, based on the text restriction, is 10
+const MAX_SEQUENCE_LENGTH: usize = 10;
 
-        drop(candidate);
+// Walk the scaffold from the robot's starting position, carrying straight on through every
+// intersection, and only turning once the scaffold ahead runs out.
+pub(super) fn find_path(camera: &CameraBuffer) -> Option<Vec<SequenceElement>> {
+    let (mut position, mut direction) = get_robot(camera)?;
+    let mut path = Vec::new();
+
+    loop {
+        let mut steps = 0;
+        while let Some(next) = scaffold_towards(camera, position, &direction) {
+            position = next;
+            steps += 1;
+        }
+        if steps > 0 {
+            path.push(SequenceElement::Forwards(steps));
+        }
 
-        // Push new candidates on to the stack
-        stack.extend(next_candidates.drain(..).rev().map(|c| c.build(context)));
+        if scaffold_towards(camera, position, &direction.turned_left()).is_some() {
+            path.push(SequenceElement::TurnLeft);
+            direction = direction.turned_left();
+        } else if scaffold_towards(camera, position, &direction.turned_right()).is_some() {
+            path.push(SequenceElement::TurnRight);
+            direction = direction.turned_right();
+        } else {
+            return Some(path);
+        }
     }
-
-    0
 }
 
-fn build_child_candidate_if_possible(
-    candidate: &Candidate,
-    context: &Context,
-    strategy: &Strategy,
-) -> Option<ChildCandidateBuilder> {
-    todo!();
-
-    // match strategy {
-    //     Strategy::AmendSequence(step) => {
-    //         if candidate.authoring.is_none() {
-    //             None
-    //         }
-    //     }
-    // }
-    // Amend is only possible if the current sequence being authored is not locked
-    // New sequence is only possible if there exists an unused slot
-    // Don't attempt a new sequence if the authored sequence is empty (first move only)
-    // A turn is only possible if they don't exceed two in a row, and if two, they should be in the same direction
-    // Playback is only possible if the slot is non-empty.
-    None
-}
-
-new_key_type! { struct CandidateId; }
-new_key_type! { struct SequenceId; }
-
-struct Context<'a> {
-    camera: &'a CameraBuffer,
-    candidates: SlotMap<CandidateId, Candidate>,
-    sequences: SlotMap<SequenceId, Sequence>,
-}
-
-impl<'a> Context<'a> {
-    fn new(camera: &'a CameraBuffer) -> Self {
-        Self {
-            camera,
-            candidates: SlotMap::with_key(),
-            sequences: SlotMap::with_key(),
-        }
+// Split the path in to a main routine which calls on the A, B and C movement functions
+pub(super) fn compress(path: &[SequenceElement]) -> Option<MovementRoutines> {
+    let mut functions: [&[SequenceElement]; 3] = [&[], &[], &[]];
+    let mut main = Vec::new();
+    if compress_from(path, &mut functions, &mut main) {
+        Some(MovementRoutines {
+            main,
+            a: functions[0].to_vec(),
+            b: functions[1].to_vec(),
+            c: functions[2].to_vec(),
+        })
+    } else {
+        None
     }
+}
 
-    fn insert_candidate(&mut self, candidate: Candidate) -> CandidateId {
-        let id = self.candidates.insert(candidate);
-        self.candidates[id].id = id;
-        id
+fn compress_from<'a>(
+    path: &'a [SequenceElement],
+    functions: &mut [&'a [SequenceElement]; 3],
+    main: &mut Vec<SequenceSlot>,
+) -> bool {
+    if path.is_empty() {
+        return true;
     }
 
-    fn insert_sequence(&mut self, sequence: Sequence) -> SequenceId {
-        let id = self.sequences.insert(sequence);
-        self.sequences[id].id = id;
-        id
+    // Each call in the main routine takes two characters, including the comma
+    if main.len() * 2 + 1 > MAX_ROUTINE_CHARS {
+        return false;
     }
 
-    fn get_robot(&self) -> Option<(Position, Direction)> {
-        let (x, y, state) = self.camera.find_robot()?;
-        let direction = match state {
-            super::RobotState::Up => Direction::Up,
-            super::RobotState::Down => Direction::Down,
-            super::RobotState::Left => Direction::Left,
-            super::RobotState::Right => Direction::Right,
-            super::RobotState::Loose => {
-                return None;
+    for (idx, slot) in ALL_SLOTS.iter().enumerate() {
+        let function = functions[idx];
+
+        // Play back a function we've already written
+        if !function.is_empty() {
+            if path.starts_with(function) {
+                main.push(*slot);
+                if compress_from(&path[function.len()..], functions, main) {
+                    return true;
+                }
+                main.pop();
             }
-        };
-        Some((Position { x, y }, direction))
-    }
-}
-
-struct Candidate {
-    id: CandidateId,
-    parent: Option<CandidateId>,
-    direction: Direction,
-    current_pos: Position,
-    traversed: Vec<Position>,
-    authoring: Option<SequenceSlot>,
-    main: Option<SequenceSlot>,
-    a: Option<SequenceId>,
-    b: Option<SequenceId>,
-    c: Option<SequenceId>,
-}
-
-impl Candidate {
-    fn initial(start_pos: Position, start_dir: Direction) -> Self {
-        let traversed = vec![start_pos];
-        Self {
-            id: CandidateId::null(),
-            parent: None,
-            direction: start_dir,
-            current_pos: start_pos,
-            traversed: traversed,
-            authoring: Some(SequenceSlot::A),
-            main: Some(SequenceSlot::A),
-            a: None,
-            b: None,
-            c: None,
+            continue;
         }
-    }
 
-    // Fold over the graph to produce the movement routine in the given slot
-    fn get_slot(&self, slot: SequenceSlot, context: &Context, buf: &mut VecDeque<SequenceElement>) {
-        buf.clear();
-        let mut parent = match slot {
-            SequenceSlot::A => self.a,
-            SequenceSlot::B => self.b,
-            SequenceSlot::C => self.c,
-        };
-
-        while let Some(s) = parent.and_then(|p| context.sequences.get(p)) {
-            buf.push_front(s.node);
-            parent = s.parent;
-        }
-    }
-
-    // Fold over the graph to produce the main program
-    fn get_main(&self, context: &Context, buf: &mut VecDeque<SequenceSlot>) {
-        buf.clear();
-        let mut candidate = Some(self);
+        // Otherwise, author the first empty function from the front of what remains.
+        // The later slots are no different, so there's no point trying them too.
+        let longest = path.len().min(MAX_SEQUENCE_LENGTH);
+        for length in (1..=longest).rev() {
+            let candidate = &path[..length];
+            if required_chars(candidate) > MAX_ROUTINE_CHARS {
+                continue;
+            }
 
-        while let Some(c) = candidate {
-            if let Some(m) = c.main {
-                buf.push_front(m);
+            functions[idx] = candidate;
+            main.push(*slot);
+            if compress_from(&path[length..], functions, main) {
+                return true;
             }
-            candidate = c.parent.and_then(|id| context.candidates.get(id));
+            main.pop();
         }
+        functions[idx] = &[];
+        break;
     }
+
+    false
 }
 
-struct ChildCandidateBuilder {
-    parent: CandidateId,
-    direction: Option<Direction>,
-    current_pos: Option<Position>,
-    traversed: Vec<Position>,
-    authoring: Option<SequenceSlot>,
-    main: Option<SequenceSlot>,
-    a: Option<SequenceBuilder>,
-    b: Option<SequenceBuilder>,
-    c: Option<SequenceBuilder>,
+fn required_chars(sequence: &[SequenceElement]) -> usize {
+    let commas = sequence.len().saturating_sub(1);
+    sequence.iter().map(|e| e.required_chars()).sum::<usize>() + commas
 }
 
-impl ChildCandidateBuilder {
-    fn new(parent: &Candidate) -> ChildCandidateBuilder {
-        ChildCandidateBuilder {
-            parent: parent.id,
-            direction: None,
-            current_pos: None,
-            traversed: Vec::with_capacity(0),
-            authoring: parent.authoring,
-            main: None,
-            a: None,
-            b: None,
-            c: None,
+fn get_robot(camera: &CameraBuffer) -> Option<(Point, Direction)> {
+    let (x, y, state) = camera.find_robot()?;
+    let direction = match state {
+        RobotState::Up => Direction::Up,
+        RobotState::Down => Direction::Down,
+        RobotState::Left => Direction::Left,
+        RobotState::Right => Direction::Right,
+        RobotState::Loose => {
+            return None;
         }
-    }
+    };
+    Some((Point::xy(x as isize, y as isize), direction))
+}
 
-    fn build(self, context: &mut Context) -> CandidateId {
-        let candidate = Candidate {
-            id: CandidateId::null(),
-            parent: Some(self.parent),
-            direction: self.direction.unwrap(),
-            current_pos: self.current_pos.unwrap(),
-            traversed: self.traversed,
-            authoring: self.authoring,
-            main: self.main,
-            a: self.a.map(|s| s.get_or_insert(context)),
-            b: self.b.map(|s| s.get_or_insert(context)),
-            c: self.c.map(|s| s.get_or_insert(context)),
-        };
-        context.insert_candidate(candidate)
+// The next position in the given direction, if it is scaffold
+fn scaffold_towards(
+    camera: &CameraBuffer,
+    position: Point,
+    direction: &Direction,
+) -> Option<Point> {
+    let (width, height) = camera.width_and_height()?;
+    let next = direction.translate_point(&position, 1, CoordinateMapping::YIncreasesDownwards);
+    let x = usize::try_from(next.x).ok().filter(|x| *x < width)?;
+    let y = usize::try_from(next.y).ok().filter(|y| *y < height)?;
+
+    if camera.is_scaffolded(x, y) {
+        Some(next)
+    } else {
+        None
     }
 }
 
-enum SequenceBuilder {
-    Existing(SequenceId),
-    New(Sequence),
+pub(super) struct MovementRoutines {
+    main: Vec<SequenceSlot>,
+    a: Vec<SequenceElement>,
+    b: Vec<SequenceElement>,
+    c: Vec<SequenceElement>,
 }
 
-impl SequenceBuilder {
-    fn get_or_insert(self, context: &mut Context) -> SequenceId {
-        match self {
-            Self::Existing(id) => id,
-            Self::New(s) => context.insert_sequence(s),
-        }
+impl MovementRoutines {
+    // The lines of text the robot expects: the main routine, then each function
+    pub(super) fn lines(&self) -> Vec<String> {
+        let main = self.main.iter().map(|s| s.to_string());
+        vec![
+            main.collect::<Vec<_>>().join(","),
+            join(&self.a),
+            join(&self.b),
+            join(&self.c),
+        ]
     }
-}
-
-// The absolute longest any sequence can be, based on the text restriction, is 10
-const MAX_SEQUENCE_LENGTH: usize = 10;
-
-struct Sequence {
-    id: SequenceId,
-    parent: Option<SequenceId>,
-    node: SequenceElement,
-    is_locked: bool,
-}
 
-#[derive(Clone, Copy)]
-struct Position {
-    x: usize,
-    y: usize,
+    #[cfg(test)]
+    fn expand(&self) -> Vec<SequenceElement> {
+        self.main
+            .iter()
+            .flat_map(|slot| match slot {
+                SequenceSlot::A => &self.a,
+                SequenceSlot::B => &self.b,
+                SequenceSlot::C => &self.c,
+            })
+            .copied()
+            .collect()
+    }
 }
 
-#[derive(Clone, Copy)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
+fn join(sequence: &[SequenceElement]) -> String {
+    let elements: Vec<String> = sequence.iter().map(|e| e.to_string()).collect();
+    elements.join(",")
 }
 
-#[derive(Clone, Copy)]
-enum SequenceElement {
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(super) enum SequenceElement {
     TurnLeft,
     TurnRight,
     Forwards(usize),
@@ -266,35 +200,75 @@ impl SequenceElement {
     }
 }
 
-const ALL_STRATEGIES: [Strategy; 9] = [
-    Strategy::AmendSequence(PathChoice::Forwards),
-    Strategy::AmendSequence(PathChoice::Left),
-    Strategy::AmendSequence(PathChoice::Right),
-    Strategy::NewSequence(PathChoice::Forwards),
-    Strategy::NewSequence(PathChoice::Left),
-    Strategy::NewSequence(PathChoice::Right),
-    Strategy::PlayBack(SequenceSlot::A),
-    Strategy::PlayBack(SequenceSlot::B),
-    Strategy::PlayBack(SequenceSlot::C),
-];
-
-#[derive(Clone, Copy)]
-enum Strategy {
-    AmendSequence(PathChoice),
-    NewSequence(PathChoice),
-    PlayBack(SequenceSlot),
+impl fmt::Display for SequenceElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TurnLeft => write!(f, "L"),
+            Self::TurnRight => write!(f, "R"),
+            Self::Forwards(n) => write!(f, "{}", n),
+        }
+    }
 }
 
-#[derive(Clone, Copy)]
-enum PathChoice {
-    Forwards,
-    Left,
-    Right,
-}
+const ALL_SLOTS: [SequenceSlot; 3] = [SequenceSlot::A, SequenceSlot::B, SequenceSlot::C];
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 enum SequenceSlot {
     A,
     B,
     C,
 }
+
+impl fmt::Display for SequenceSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::A => write!(f, "A"),
+            Self::B => write!(f, "B"),
+            Self::C => write!(f, "C"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "#######...#####
+#.....#...#...#
+#.....#...#...#
+......#...#...#
+......#...###.#
+......#.....#.#
+^########...#.#
+......#.#...#.#
+......#########
+........#...#..
+....#########..
+....#...#......
+....#...#......
+....#...#......
+....#####......
+";
+
+    #[test]
+    fn finds_and_compresses_the_example_path() {
+        let camera = CameraBuffer::from_text(EXAMPLE);
+        let path = find_path(&camera).unwrap();
+        assert_eq!(
+            "R,8,R,8,R,4,R,4,R,8,L,6,L,2,R,4,R,4,R,8,R,8,R,8,L,6,L,2",
+            join(&path)
+        );
+
+        let routines = compress(&path).unwrap();
+        assert_eq!(path, routines.expand());
+        for line in routines.lines() {
+            assert!(line.len() <= MAX_ROUTINE_CHARS, "{} is too long", line);
+        }
+    }
+
+    #[test]
+    fn long_paths_do_not_compress() {
+        let path: Vec<SequenceElement> = (1..30).map(SequenceElement::Forwards).collect();
+        assert!(compress(&path).is_none());
+    }
+}
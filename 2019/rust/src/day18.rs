@@ -0,0 +1,382 @@
+use crate::solution::{self, Skipped, Solution};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+
+type Point = crate::util::Point<usize>;
+
+// A bit for each key, which is also the bit for the door that it opens
+type KeySet = u32;
+
+const INPUT_PATH: &str = "inputs/day18.txt";
+
+pub fn run_day_eighteen() {
+    solution::run::<Solver>();
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2019;
+    const DAY: u32 = 18;
+
+    type Input = Vault;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse() -> Result<Vault, Skipped> {
+        Ok(Vault::from_string(&solution::read_input(INPUT_PATH)?))
+    }
+
+    fn part_one(input: &Vault) -> usize {
+        input.shortest_path().unwrap()
+    }
+
+    fn part_two(input: &Vault) -> usize {
+        input.split_entrance().shortest_path().unwrap()
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Tile {
+    Wall,
+    Open,
+    Entrance,
+    Key(usize),
+    Door(usize),
+}
+
+impl Tile {
+    fn from_char(c: char) -> Tile {
+        match c {
+            '#' => Tile::Wall,
+            '.' => Tile::Open,
+            '@' => Tile::Entrance,
+            'a'..='z' => Tile::Key(c as usize - 'a' as usize),
+            'A'..='Z' => Tile::Door(c as usize - 'A' as usize),
+            _ => panic!("Unexpected tile: {}", c),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Vault {
+    grid: Vec<Vec<Tile>>,
+}
+
+// The shortest way from one point of interest to a key, the doors along the way,
+// and any other keys which are picked up by walking over them
+#[derive(Debug)]
+struct Route {
+    key: usize,
+    distance: usize,
+    doors: KeySet,
+    keys: KeySet,
+}
+
+// The points of interest are the keys, numbered by their letter, followed by the entrances
+struct Graph {
+    routes: Vec<Vec<Route>>,
+    entrances: Vec<usize>,
+    all_keys: KeySet,
+}
+
+impl Vault {
+    fn from_string(s: &str) -> Vault {
+        let grid = s
+            .lines()
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty())
+            .map(|l| l.chars().map(Tile::from_char).collect())
+            .collect();
+        Vault { grid }
+    }
+
+    fn get(&self, point: Point) -> Tile {
+        self.grid
+            .get(point.y)
+            .and_then(|row| row.get(point.x))
+            .copied()
+            .unwrap_or(Tile::Wall)
+    }
+
+    fn find_all(&self, predicate: impl Fn(Tile) -> bool) -> Vec<(Point, Tile)> {
+        let mut found = Vec::new();
+        for (y, row) in self.grid.iter().enumerate() {
+            for (x, tile) in row.iter().enumerate() {
+                if predicate(*tile) {
+                    found.push((Point::xy(x, y), *tile));
+                }
+            }
+        }
+        found
+    }
+
+    // Wall off the middle of the vault, leaving a robot in each corner of the entrance
+    fn split_entrance(&self) -> Vault {
+        let entrances = self.find_all(|t| t == Tile::Entrance);
+        if entrances.len() != 1 {
+            return self.clone();
+        }
+
+        let Point { x, y } = entrances[0].0;
+        let mut vault = self.clone();
+        for (dy, row) in ["@#@", "###", "@#@"].iter().enumerate() {
+            for (dx, c) in row.chars().enumerate() {
+                vault.grid[y + dy - 1][x + dx - 1] = Tile::from_char(c);
+            }
+        }
+        vault
+    }
+
+    fn build_graph(&self) -> Graph {
+        let keys = self.find_all(|t| matches!(t, Tile::Key(_)));
+        let entrances = self.find_all(|t| t == Tile::Entrance);
+
+        let mut starts: Vec<Point> = vec![Point::xy(0, 0); 26];
+        let mut all_keys: KeySet = 0;
+        for (point, tile) in keys.iter() {
+            if let Tile::Key(k) = tile {
+                starts[*k] = *point;
+                all_keys |= 1 << k;
+            }
+        }
+        let entrance_ids = (26..26 + entrances.len()).collect();
+        starts.extend(entrances.iter().map(|(p, _)| *p));
+
+        let routes = starts
+            .iter()
+            .enumerate()
+            .map(|(id, start)| match id < 26 && all_keys & (1 << id) == 0 {
+                true => Vec::new(),
+                false => self.routes_from(*start),
+            })
+            .collect();
+
+        Graph {
+            routes,
+            entrances: entrance_ids,
+            all_keys,
+        }
+    }
+
+    // Breadth first search to every key, ignoring whether the doors are locked
+    fn routes_from(&self, start: Point) -> Vec<Route> {
+        let mut routes = Vec::new();
+        let mut seen = vec![vec![false; self.grid[0].len()]; self.grid.len()];
+        let mut queue: VecDeque<(Point, usize, KeySet, KeySet)> = VecDeque::new();
+        seen[start.y][start.x] = true;
+        queue.push_back((start, 0, 0, 0));
+
+        while let Some((point, distance, doors, keys)) = queue.pop_front() {
+            let neighbours = [
+                Point::xy(point.x + 1, point.y),
+                Point::xy(point.x, point.y + 1),
+                Point::xy(point.x.wrapping_sub(1), point.y),
+                Point::xy(point.x, point.y.wrapping_sub(1)),
+            ];
+
+            for next in neighbours {
+                let tile = self.get(next);
+                if tile == Tile::Wall || seen[next.y][next.x] {
+                    continue;
+                }
+                seen[next.y][next.x] = true;
+
+                match tile {
+                    Tile::Key(key) => {
+                        routes.push(Route {
+                            key,
+                            distance: distance + 1,
+                            doors,
+                            keys,
+                        });
+                        queue.push_back((next, distance + 1, doors, keys | (1 << key)));
+                    }
+                    Tile::Door(door) => {
+                        queue.push_back((next, distance + 1, doors | (1 << door), keys))
+                    }
+                    _ => queue.push_back((next, distance + 1, doors, keys)),
+                }
+            }
+        }
+
+        routes
+    }
+
+    // Dijkstra over the position of every robot, and the keys collected so far
+    fn shortest_path(&self) -> Option<usize> {
+        let graph = self.build_graph();
+        let start = (graph.entrances.clone(), 0 as KeySet);
+
+        let mut best: HashMap<(Vec<usize>, KeySet), usize> = HashMap::new();
+        let mut queue = BinaryHeap::new();
+        best.insert(start.clone(), 0);
+        queue.push(Reverse((0, start)));
+
+        while let Some(Reverse((distance, (positions, keys)))) = queue.pop() {
+            if keys == graph.all_keys {
+                return Some(distance);
+            }
+            if best.get(&(positions.clone(), keys)) < Some(&distance) {
+                continue;
+            }
+
+            for (robot, position) in positions.iter().enumerate() {
+                for route in graph.routes[*position].iter() {
+                    let is_new = keys & (1 << route.key) == 0;
+                    let is_unlocked = route.doors & !keys == 0;
+                    if !is_new || !is_unlocked {
+                        continue;
+                    }
+
+                    let mut next_positions = positions.clone();
+                    next_positions[robot] = route.key;
+                    let next = (next_positions, keys | route.keys | (1 << route.key));
+                    let next_distance = distance + route.distance;
+                    if best.get(&next).is_none_or(|d| next_distance < *d) {
+                        best.insert(next.clone(), next_distance);
+                        queue.push(Reverse((next_distance, next)));
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shortest(s: &str) -> usize {
+        Vault::from_string(s).shortest_path().unwrap()
+    }
+
+    fn shortest_split(s: &str) -> usize {
+        Vault::from_string(s)
+            .split_entrance()
+            .shortest_path()
+            .unwrap()
+    }
+
+    #[test]
+    fn part_one_examples() {
+        assert_eq!(
+            8,
+            shortest(
+                "
+                #########
+                #b.A.@.a#
+                #########"
+            )
+        );
+        assert_eq!(
+            86,
+            shortest(
+                "
+                ########################
+                #f.D.E.e.C.b.A.@.a.B.c.#
+                ######################.#
+                #d.....................#
+                ########################"
+            )
+        );
+        assert_eq!(
+            132,
+            shortest(
+                "
+                ########################
+                #...............b.C.D.f#
+                #.######################
+                #.....@.a.B.c.d.A.e.F.g#
+                ########################"
+            )
+        );
+        assert_eq!(
+            136,
+            shortest(
+                "
+                #################
+                #i.G..c...e..H.p#
+                ########.########
+                #j.A..b...f..D.o#
+                ########@########
+                #k.E..a...g..B.n#
+                ########.########
+                #l.F..d...h..C.m#
+                #################"
+            )
+        );
+        assert_eq!(
+            81,
+            shortest(
+                "
+                ########################
+                #@..............ac.GI.b#
+                ###d#e#f################
+                ###A#B#C################
+                ###g#h#i################
+                ########################"
+            )
+        );
+    }
+
+    #[test]
+    fn part_two_examples() {
+        assert_eq!(
+            8,
+            shortest_split(
+                "
+                #######
+                #a.#Cd#
+                ##...##
+                ##.@.##
+                ##...##
+                #cB#Ab#
+                #######"
+            )
+        );
+        assert_eq!(
+            24,
+            shortest_split(
+                "
+                ###############
+                #d.ABC.#.....a#
+                ######@#@######
+                ###############
+                ######@#@######
+                #b.....#.....c#
+                ###############"
+            )
+        );
+        assert_eq!(
+            32,
+            shortest_split(
+                "
+                #############
+                #DcBa.#.GhKl#
+                #.###@#@#I###
+                #e#d#####j#k#
+                ###C#@#@###J#
+                #fEbA.#.FgHi#
+                #############"
+            )
+        );
+        assert_eq!(
+            72,
+            shortest_split(
+                "
+                #############
+                #g#f.D#..h#l#
+                #F###e#E###.#
+                #dCba@#@BcIJ#
+                #############
+                #nK.L@#@G...#
+                #M###N#H###.#
+                #o#m..#i#jk.#
+                #############"
+            )
+        );
+    }
+}
@@ -0,0 +1,257 @@
+use crate::intcode::*;
+use crate::solution::{self, Skipped, Solution};
+
+const INPUT_PATH: &str = "inputs/day19.txt";
+const SCAN_SIZE: MemoryCell = 50;
+const SHIP_SIZE: MemoryCell = 100;
+
+pub fn run_day_nineteen() {
+    solution::run::<Solver>();
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2019;
+    const DAY: u32 = 19;
+
+    type Input = Vec<MemoryCell>;
+    type PartOne = usize;
+    type PartTwo = MemoryCell;
+
+    fn parse() -> Result<Vec<MemoryCell>, Skipped> {
+        Ok(crate::util::parse_int_array(&solution::read_input(
+            INPUT_PATH,
+        )?))
+    }
+
+    fn part_one(input: &Vec<MemoryCell>) -> usize {
+        survey(input, |drone| count_affected(drone, SCAN_SIZE)).answer
+    }
+
+    fn part_two(input: &Vec<MemoryCell>) -> MemoryCell {
+        let (x, y) = survey(input, |drone| find_square(drone, SHIP_SIZE)).answer;
+        x * 10000 + y
+    }
+}
+
+// An answer, along with how many times the drone program ran to find it
+#[derive(Debug, PartialEq)]
+struct Survey<T> {
+    answer: T,
+    runs: usize,
+}
+
+fn survey<T>(program: &[MemoryCell], search: impl FnOnce(&mut Drone) -> T) -> Survey<T> {
+    let mut drone = Drone::new(program.to_vec());
+    let answer = search(&mut drone);
+    Survey {
+        answer,
+        runs: drone.runs(),
+    }
+}
+
+// The drone program answers a single query and then halts,
+// so the computer is reset back to the pristine program before each one
+struct Drone {
+    program: Vec<MemoryCell>,
+    computer: Computer<PagedStorage, BufferInput, BufferOutput>,
+    runs: usize,
+}
+
+impl Drone {
+    fn new(program: Vec<MemoryCell>) -> Drone {
+        let computer = Computer::new(
+            PagedStorage::from(program.as_slice()),
+            BufferInput::new(2),
+            BufferOutput::new(1),
+        );
+        Drone {
+            program,
+            computer,
+            runs: 0,
+        }
+    }
+
+    fn is_pulled(&mut self, x: MemoryCell, y: MemoryCell) -> bool {
+        self.computer
+            .reset(&self.program)
+            .expect("The program fits in memory");
+        self.computer.input().queue_many(&[x, y]);
+        self.computer
+            .run_until_halt()
+            .expect("The drone reports back");
+        self.runs += 1;
+
+        match self.computer.output().pop() {
+            Some(0) => false,
+            Some(1) => true,
+            other => panic!("Unexpected drone report: {:?}", other),
+        }
+    }
+
+    fn runs(&self) -> usize {
+        self.runs
+    }
+}
+
+fn count_affected(drone: &mut Drone, size: MemoryCell) -> usize {
+    let mut affected = 0;
+    for y in 0..size {
+        for x in 0..size {
+            if drone.is_pulled(x, y) {
+                affected += 1;
+            }
+        }
+    }
+    affected
+}
+
+// Every ray out from the emitter crosses each square ring around it, so searching the rings
+// in turn, closest first, is sure to find the beam. Returns the row it was found on, along
+// with the beam's left edge on that row.
+fn find_beam(drone: &mut Drone) -> (MemoryCell, MemoryCell) {
+    for ring in 1.. {
+        let row = (0..=ring).map(|x| (x, ring));
+        let column = (1..ring).map(|y| (ring, y));
+        let found = row.chain(column).find(|&(x, y)| drone.is_pulled(x, y));
+        if let Some((mut x, y)) = found {
+            while x > 0 && drone.is_pulled(x - 1, y) {
+                x -= 1;
+            }
+            return (y, x);
+        }
+    }
+    unreachable!()
+}
+
+// Follow the left edge of the beam down, row by row, until a square fits above it.
+// With the bottom left corner of the square on the edge, it fits if the top right corner is
+// also in the beam. The edge only ever moves right, so each row costs just a few runs.
+fn find_square(drone: &mut Drone, size: MemoryCell) -> (MemoryCell, MemoryCell) {
+    let (mut edge_row, mut left) = find_beam(drone);
+    for y in edge_row.. {
+        // The beam is a cone, so its left edge can't be any further right than the line from
+        // the emitter through the last edge we saw. If the beam isn't there by then, the row is
+        // close enough to the emitter that the beam misses every cell.
+        let furthest = (left * y + edge_row - 1) / edge_row;
+        let x = match (left..=furthest).find(|&x| drone.is_pulled(x, y)) {
+            Some(x) => x,
+            None => continue,
+        };
+        edge_row = y;
+        left = x;
+
+        if y >= size - 1 {
+            let top = y - (size - 1);
+            if drone.is_pulled(x + size - 1, top) {
+                return (x, top);
+            }
+        }
+    }
+    unreachable!()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A stand in for the puzzle input: a beam between y = x and y = 1.5x
+    const BEAM: &str = "
+                in   [x]
+                in   [y]
+                mul  [x], #3, [x3]
+                mul  [y], #2, [y2]
+                lt   [x3], [y2], [below]
+                lt   [y], [x], [above]
+                add  [below], [above], [outside]
+                eq   [outside], #0, [result]
+                out  [result]
+                hlt
+        x:      data 0
+        y:      data 0
+        x3:     data 0
+        y2:     data 0
+        below:  data 0
+        above:  data 0
+        outside: data 0
+        result: data 0
+    ";
+
+    fn in_beam(x: MemoryCell, y: MemoryCell) -> bool {
+        x <= y && 2 * y <= 3 * x
+    }
+
+    #[test]
+    fn scans_the_beam() {
+        let expected = (0..10)
+            .flat_map(|y| (0..10).map(move |x| (x, y)))
+            .filter(|(x, y)| in_beam(*x, *y))
+            .count();
+        assert_eq!(
+            Survey {
+                answer: expected,
+                runs: 100
+            },
+            survey(&assemble(BEAM).unwrap(), |drone| count_affected(drone, 10))
+        );
+    }
+
+    #[test]
+    fn finds_the_closest_square() {
+        for size in [10, 100] {
+            let fits = |x, y| in_beam(x + size - 1, y) && in_beam(x, y + size - 1);
+            let expected = (0..1000)
+                .flat_map(|y| (0..1000).map(move |x| (x, y)))
+                .find(|(x, y)| fits(*x, *y))
+                .unwrap();
+
+            let result = survey(&assemble(BEAM).unwrap(), |drone| find_square(drone, size));
+            assert_eq!(expected, result.answer);
+            assert!(result.runs < 2000, "Took {} runs", result.runs);
+        }
+    }
+
+    #[test]
+    fn finds_a_narrow_beam_far_from_the_axis() {
+        // A beam between x = 5.1y and x = 5.4y, which misses every cell on the first two rows
+        let program = assemble(
+            "
+                    in   [x]
+                    in   [y]
+                    mul  [x], #10, [x10]
+                    mul  [y], #51, [y51]
+                    mul  [y], #54, [y54]
+                    lt   [x10], [y51], [below]
+                    lt   [y54], [x10], [above]
+                    add  [below], [above], [outside]
+                    eq   [outside], #0, [result]
+                    out  [result]
+                    hlt
+            x:      data 0
+            y:      data 0
+            x10:    data 0
+            y51:    data 0
+            y54:    data 0
+            below:  data 0
+            above:  data 0
+            outside: data 0
+            result: data 0
+            ",
+        )
+        .unwrap();
+        let in_beam = |x: MemoryCell, y: MemoryCell| 51 * y <= 10 * x && 10 * x <= 54 * y;
+        assert!((0..20).all(|x| !in_beam(x, 1) && !in_beam(x, 2)));
+
+        let size = 5;
+        let fits = |x, y| in_beam(x + size - 1, y) && in_beam(x, y + size - 1);
+        let expected = (0..500)
+            .flat_map(|y| (0..3000).map(move |x| (x, y)))
+            .find(|(x, y)| fits(*x, *y))
+            .unwrap();
+
+        let result = survey(&program, |drone| find_square(drone, size));
+        assert_eq!(expected, result.answer);
+        assert!(result.runs < 2000, "Took {} runs", result.runs);
+    }
+}
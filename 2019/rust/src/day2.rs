@@ -1,4 +1,5 @@
 use crate::intcode::*;
+use crate::solution::{self, Skipped, Solution};
 use crate::util;
 use rayon::prelude::*;
 
@@ -6,9 +7,11 @@ fn input() -> Vec<MemoryCell> {
     util::read_int_array("inputs/day2.txt")
 }
 
-fn find_required_values(input: Vec<MemoryCell>) -> isize {
+fn find_required_values(input: Vec<MemoryCell>) -> MemoryCell {
     const TARGET_RESULT: MemoryCell = 19690720;
-    let found = (0..=9999isize)
+    // Plenty for the real program; a bad noun or verb could otherwise spin forever
+    const BUDGET: usize = 10_000;
+    let found = (0..=9999)
         .into_par_iter()
         .map(|x| {
             let i = x / 100;
@@ -16,26 +19,40 @@ fn find_required_values(input: Vec<MemoryCell>) -> isize {
             let mut inputs = input.clone();
             inputs[1] = i;
             inputs[2] = j;
-            let result = run_basic_intcode_program(inputs, 0).unwrap();
+            let result = run_budgeted_intcode_program(inputs, 0, BUDGET).ok();
             (x, result)
         })
-        .find_any(|(_x, result)| *result == TARGET_RESULT)
+        .find_any(|(_x, result)| *result == Some(TARGET_RESULT))
         .unwrap();
 
     found.0
 }
 
-fn calculate_day_two() -> (isize, isize) {
-    let input = input();
-    let part_one = run_basic_intcode_program(input.clone(), 0).unwrap();
-    let part_two = find_required_values(input);
-    (part_one, part_two)
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2019;
+    const DAY: u32 = 2;
+
+    type Input = Vec<MemoryCell>;
+    type PartOne = MemoryCell;
+    type PartTwo = MemoryCell;
+
+    fn parse() -> Result<Vec<MemoryCell>, Skipped> {
+        Ok(input())
+    }
+
+    fn part_one(input: &Vec<MemoryCell>) -> MemoryCell {
+        run_basic_intcode_program(input.clone(), 0).unwrap()
+    }
+
+    fn part_two(input: &Vec<MemoryCell>) -> MemoryCell {
+        find_required_values(input.clone())
+    }
 }
 
 pub fn run_day_two() {
-    let (part_one, part_two) = calculate_day_two();
-    println!("Day 2, Part 1: {}", part_one);
-    println!("Day 2, Part 2: {}", part_two);
+    solution::run::<Solver>();
 }
 
 #[test]
@@ -72,7 +89,7 @@ fn example_4() {
 
 #[test]
 fn actual_day_2() {
-    let (part_one, part_two) = calculate_day_two();
+    let (part_one, part_two) = solution::answers::<Solver>();
     assert_eq!(3706713, part_one);
     assert_eq!(8609, part_two);
 }
@@ -0,0 +1,266 @@
+use crate::solution::{self, Skipped, Solution};
+use crate::util::{CoordinateMapping, Direction};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+type Point = crate::util::Point<i32>;
+
+const INPUT_PATH: &str = "inputs/day20.txt";
+
+// Deep enough for any of the puzzle mazes, while stopping the search if there's no way out
+const MAX_LEVEL: usize = 128;
+
+pub fn run_day_twenty() {
+    solution::run::<Solver>();
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2019;
+    const DAY: u32 = 20;
+
+    type Input = Maze;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse() -> Result<Maze, Skipped> {
+        Ok(Maze::from_string(&solution::read_input(INPUT_PATH)?))
+    }
+
+    fn part_one(input: &Maze) -> usize {
+        input.shortest_path(false).unwrap()
+    }
+
+    fn part_two(input: &Maze) -> usize {
+        input.shortest_path(true).unwrap()
+    }
+}
+
+const ALL_DIRECTIONS: [Direction; 4] = [
+    Direction::Up,
+    Direction::Down,
+    Direction::Left,
+    Direction::Right,
+];
+
+fn step(from: &Point, direction: &Direction) -> Point {
+    direction.translate_point(from, 1, CoordinateMapping::YIncreasesDownwards)
+}
+
+// Where a portal leads, and whether it takes us in to a deeper level or back out of one
+#[derive(Debug, Clone, Copy)]
+struct Portal {
+    to: Point,
+    is_inner: bool,
+}
+
+pub struct Maze {
+    open: HashSet<Point>,
+    portals: HashMap<Point, Portal>,
+    start: Point,
+    end: Point,
+}
+
+impl Maze {
+    fn from_string(s: &str) -> Maze {
+        let grid: Vec<Vec<char>> = s
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| l.chars().collect())
+            .collect();
+        let height = grid.len() as i32;
+        let width = grid.iter().map(|row| row.len()).max().unwrap_or(0) as i32;
+        let at = |p: Point| -> char {
+            if p.x < 0 || p.y < 0 {
+                return ' ';
+            }
+            grid.get(p.y as usize)
+                .and_then(|row| row.get(p.x as usize))
+                .copied()
+                .unwrap_or(' ')
+        };
+
+        let mut open = HashSet::new();
+        let mut labelled: HashMap<String, Vec<Point>> = HashMap::new();
+        for y in 0..height {
+            for x in 0..width {
+                let here = Point::xy(x, y);
+                if at(here) == '.' {
+                    open.insert(here);
+                }
+
+                // Labels read left to right or top to bottom. The open tile next to
+                // either end of the label is the one that the label names.
+                let first = at(here);
+                if !first.is_ascii_uppercase() {
+                    continue;
+                }
+                for direction in [Direction::Right, Direction::Down] {
+                    let next = step(&here, &direction);
+                    let second = at(next);
+                    if !second.is_ascii_uppercase() {
+                        continue;
+                    }
+
+                    let before = Point::xy(2 * here.x - next.x, 2 * here.y - next.y);
+                    let after = step(&next, &direction);
+                    let tile = if at(before) == '.' { before } else { after };
+                    let label: String = [first, second].iter().collect();
+                    labelled.entry(label).or_default().push(tile);
+                }
+            }
+        }
+
+        let is_outer = |p: &Point| p.x == 2 || p.y == 2 || p.x == width - 3 || p.y == height - 3;
+        let mut portals = HashMap::new();
+        for (label, tiles) in labelled.iter() {
+            if let [a, b] = tiles.as_slice() {
+                portals.insert(
+                    *a,
+                    Portal {
+                        to: *b,
+                        is_inner: !is_outer(a),
+                    },
+                );
+                portals.insert(
+                    *b,
+                    Portal {
+                        to: *a,
+                        is_inner: !is_outer(b),
+                    },
+                );
+            } else if tiles.len() != 1 {
+                panic!("Portal {} should have two ends", label);
+            }
+        }
+
+        Maze {
+            open,
+            portals,
+            start: labelled["AA"][0],
+            end: labelled["ZZ"][0],
+        }
+    }
+
+    // Breadth first search over the position and level. In a flat maze every portal keeps
+    // us on the same level. Otherwise inner portals go down a level, and outer portals
+    // come back up, except on the top level where they're just walls.
+    fn shortest_path(&self, is_recursive: bool) -> Option<usize> {
+        let mut seen: HashSet<(Point, usize)> = HashSet::new();
+        let mut queue: VecDeque<(Point, usize, usize)> = VecDeque::new();
+        seen.insert((self.start, 0));
+        queue.push_back((self.start, 0, 0));
+
+        while let Some((position, level, distance)) = queue.pop_front() {
+            if position == self.end && level == 0 {
+                return Some(distance);
+            }
+
+            let walks = ALL_DIRECTIONS
+                .iter()
+                .map(|d| step(&position, d))
+                .filter(|p| self.open.contains(p))
+                .map(|p| (p, level));
+
+            let jump = self.portals.get(&position).and_then(|portal| {
+                match (is_recursive, portal.is_inner) {
+                    (false, _) => Some((portal.to, level)),
+                    (true, true) if level < MAX_LEVEL => Some((portal.to, level + 1)),
+                    (true, false) if level > 0 => Some((portal.to, level - 1)),
+                    _ => None,
+                }
+            });
+
+            for next in walks.chain(jump) {
+                if seen.insert(next) {
+                    queue.push_back((next.0, next.1, distance + 1));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIRST_EXAMPLE: &str = "
+         A
+         A
+  #######.#########
+  #######.........#
+  #######.#######.#
+  #######.#######.#
+  #######.#######.#
+  #####  B    ###.#
+BC...##  C    ###.#
+  ##.##       ###.#
+  ##...DE  F  ###.#
+  #####    G  ###.#
+  #########.#####.#
+DE..#######...###.#
+  #.#########.###.#
+FG..#########.....#
+  ###########.#####
+             Z
+             Z
+";
+
+    const RECURSIVE_EXAMPLE: &str = "
+             Z L X W       C
+             Z P Q B       K
+  ###########.#.#.#.#######.###############
+  #...#.......#.#.......#.#.......#.#.#...#
+  ###.#.#.#.#.#.#.#.###.#.#.#######.#.#.###
+  #.#...#.#.#...#.#.#...#...#...#.#.......#
+  #.###.#######.###.###.#.###.###.#.#######
+  #...#.......#.#...#...#.............#...#
+  #.#########.#######.#.#######.#######.###
+  #...#.#    F       R I       Z    #.#.#.#
+  #.###.#    D       E C       H    #.#.#.#
+  #.#...#                           #...#.#
+  #.###.#                           #.###.#
+  #.#....OA                       WB..#.#..ZH
+  #.###.#                           #.#.#.#
+CJ......#                           #.....#
+  #######                           #######
+  #.#....CK                         #......IC
+  #.###.#                           #.###.#
+  #.....#                           #...#.#
+  ###.###                           #.#.#.#
+XF....#.#                         RF..#.#.#
+  #####.#                           #######
+  #......CJ                       NM..#...#
+  ###.#.#                           #.###.#
+RE....#.#                           #......RF
+  ###.###        X   X       L      #.#.#.#
+  #.....#        F   Q       P      #.#.#.#
+  ###.###########.###.#######.#########.###
+  #.....#...#.....#.......#...#.....#.#...#
+  #####.#.###.#######.#######.###.###.#.#.#
+  #.......#.......#.#.#.#.#...#...#...#.#.#
+  #####.###.#####.#.#.#.#.###.###.#.###.###
+  #.......#.....#.#...#...............#...#
+  #############.#.#.###.###################
+               A O F   N
+               A A D   M
+";
+
+    #[test]
+    fn flat_maze() {
+        let maze = Maze::from_string(FIRST_EXAMPLE);
+        assert_eq!(Some(23), maze.shortest_path(false));
+    }
+
+    #[test]
+    fn recursive_maze() {
+        // Only the long way round avoids going through the outer portals on the top level
+        let maze = Maze::from_string(FIRST_EXAMPLE);
+        assert_eq!(Some(26), maze.shortest_path(true));
+
+        let maze = Maze::from_string(RECURSIVE_EXAMPLE);
+        assert_eq!(Some(396), maze.shortest_path(true));
+    }
+}
@@ -0,0 +1,208 @@
+use crate::intcode::{AsciiInput, AsciiOutput, Computer, MemoryCell, PagedStorage};
+use crate::solution::{self, Skipped, Solution};
+use springscript::{Instruction, Mode, Register, Script};
+
+mod search;
+mod springscript;
+
+const INPUT_PATH: &str = "inputs/day21.txt";
+
+pub fn run_day_twenty_one() {
+    solution::run::<Solver>();
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2019;
+    const DAY: u32 = 21;
+
+    type Input = Vec<MemoryCell>;
+    type PartOne = MemoryCell;
+    type PartTwo = MemoryCell;
+
+    fn parse() -> Result<Vec<MemoryCell>, Skipped> {
+        Ok(crate::util::parse_int_array(&solution::read_input(
+            INPUT_PATH,
+        )?))
+    }
+
+    fn part_one(input: &Vec<MemoryCell>) -> MemoryCell {
+        discover_walk_script(input).damage
+    }
+
+    fn part_two(input: &Vec<MemoryCell>) -> MemoryCell {
+        match run_script(input, &run_script_by_hand()) {
+            Report::HullDamage(damage) => damage,
+            Report::Fell(animation) => panic!("The droid didn't make it across:\n{}", animation),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Report {
+    HullDamage(MemoryCell),
+    Fell(String),
+}
+
+// The droid reports the hull damage if it makes it across, otherwise it draws its fall
+fn run_script(program: &[MemoryCell], script: &Script) -> Report {
+    let mut computer = Computer::new(
+        PagedStorage::from(program),
+        AsciiInput::new(),
+        AsciiOutput::new(),
+    );
+    for line in script.lines() {
+        computer.input().queue_line(&line);
+    }
+    computer
+        .run_until_halt()
+        .expect("The springdroid ran to completion");
+
+    match computer.output().last_non_ascii() {
+        Some(damage) => Report::HullDamage(damage),
+        None => Report::Fell(computer.output().text().to_string()),
+    }
+}
+
+// The hull damage reported once the droid made it across. The tests also check which
+// script got it there, and how many times it fell before that one was found.
+struct Discovery {
+    damage: MemoryCell,
+    #[cfg(test)]
+    script: Script,
+    #[cfg(test)]
+    falls: usize,
+}
+
+// Each time the droid falls, remember the hull that it fell on,
+// and search for a script that would have made it across all of them
+fn discover_walk_script(program: &[MemoryCell]) -> Discovery {
+    let mut hulls = Vec::new();
+    loop {
+        let script = search::find_walk_script(&hulls).expect("Some script crosses every hull");
+        match run_script(program, &script) {
+            Report::HullDamage(damage) => {
+                return Discovery {
+                    damage,
+                    #[cfg(test)]
+                    script,
+                    #[cfg(test)]
+                    falls: hulls.len(),
+                }
+            }
+            Report::Fell(animation) => {
+                let hull = search::parse_hull(&animation).expect("The animation shows the hull");
+                assert!(!hulls.contains(&hull), "Fell on the same hull twice");
+                hulls.push(hull);
+            }
+        }
+    }
+}
+
+// Jump if there's a hole in the next three tiles and somewhere to land. Then, if the tile
+// after landing is a hole, only jump if it will be possible to jump again straight away.
+fn run_script_by_hand() -> Script {
+    use Register::*;
+    Script::new(
+        vec![
+            Instruction::not(A, J),
+            Instruction::not(B, T),
+            Instruction::or(T, J),
+            Instruction::not(C, T),
+            Instruction::or(T, J),
+            Instruction::and(D, J),
+            Instruction::not(E, T),
+            Instruction::not(T, T),
+            Instruction::or(H, T),
+            Instruction::and(T, J),
+        ],
+        Mode::Run,
+    )
+    .expect("The script fits in the droid")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intcode::assemble;
+
+    // A stand in for the springdroid, which waits for the mode and then
+    // either reports some hull damage or draws a very short hull
+    fn droid(makes_it: bool) -> Vec<MemoryCell> {
+        let source = format!(
+            "
+            read:   in   [c]
+                    eq   [c], #75, [done]    ; The K at the end of WALK
+                    jf   [done], #read
+                    jt   #{}, #fall
+                    out  #19355
+                    hlt
+            fall:   out  #35
+                    out  #46
+                    out  #35
+                    out  #10
+                    hlt
+            c:      data 0
+            done:   data 0
+            ",
+            if makes_it { 0 } else { 1 }
+        );
+        assemble(&source).unwrap()
+    }
+
+    #[test]
+    fn tells_damage_from_falls() {
+        let script = search::find_walk_script(&[]).unwrap();
+        assert_eq!(Report::HullDamage(19355), run_script(&droid(true), &script));
+
+        let fell = run_script(&droid(false), &script);
+        assert_eq!(Report::Fell("#.#\n".to_string()), fell);
+    }
+
+    #[test]
+    fn discovers_a_script_after_falling() {
+        // Falls in the hole unless the script has at least one instruction before WALK
+        let program = assemble(
+            "
+                    in   [first]
+                    eq   [first], #87, [empty]  ; The W of WALK
+            read:   in   [c]
+                    eq   [c], #10, [done]
+                    jf   [done], #read
+                    jt   [empty], #fall
+                    out  #19355
+                    hlt
+            fall:   out  #35
+                    out  #46
+                    out  #35
+                    out  #10
+                    hlt
+            first:  data 0
+            empty:  data 0
+            c:      data 0
+            done:   data 0
+            ",
+        )
+        .unwrap();
+
+        let discovery = discover_walk_script(&program);
+        assert_eq!(19355, discovery.damage);
+        assert_eq!(1, discovery.falls);
+        assert!(!discovery.script.instructions().is_empty());
+    }
+
+    #[test]
+    fn hand_written_run_script() {
+        let script = run_script_by_hand();
+        assert_eq!("RUN", script.lines().last().unwrap());
+        for hull in [
+            "#####...#########",
+            "#####.#.##.#.####",
+            "#####.##.##.#####",
+        ] {
+            let hull = search::parse_hull(hull).unwrap();
+            assert!(search::survives(&hull, |sensors| script.jumps(sensors)));
+        }
+    }
+}
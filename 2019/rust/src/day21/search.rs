@@ -0,0 +1,207 @@
+use super::springscript::{Instruction, Mode, Op, Register, Script, MAX_INSTRUCTIONS, SENSORS};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+// A stretch of hull, where true is ground and false is a hole
+pub(super) type Hull = Vec<bool>;
+
+// Every combination of what the four WALK sensors can see gets one bit in a truth table
+type TruthTable = u16;
+
+const WALK_SENSORS: usize = 4;
+
+// The droid's view of the hull in the failure animation is the first row starting on ground
+pub(super) fn parse_hull(animation: &str) -> Option<Hull> {
+    let row = animation.lines().find(|l| l.starts_with('#'))?;
+    Some(row.chars().map(|c| c == '#').collect())
+}
+
+// Walk the droid along the hull, deciding whether to jump at each step.
+// Jumps land four tiles ahead, and anything off the end of the hull is ground.
+pub(super) fn survives(hull: &[bool], jumps: impl Fn(&[bool]) -> bool) -> bool {
+    let ground = |at: usize| hull.get(at).copied().unwrap_or(true);
+    let mut position = 0;
+    while position < hull.len() {
+        if !ground(position) {
+            return false;
+        }
+
+        let sensors: Vec<bool> = (1..=SENSORS.len()).map(|d| ground(position + d)).collect();
+        position += if jumps(&sensors) { 4 } else { 1 };
+    }
+    true
+}
+
+// Find the shortest WALK script which makes it across every hull.
+//
+// First work out which truth tables would make it, by trying them all. Then search outwards
+// from an empty script, one instruction at a time, evaluating the T and J registers for every
+// combination of sensors at once, until J holds one of those tables.
+pub(super) fn find_walk_script(hulls: &[Hull]) -> Option<Script> {
+    let acceptable: HashSet<TruthTable> = (0..=TruthTable::MAX)
+        .filter(|table| {
+            hulls
+                .iter()
+                .all(|hull| survives(hull, |sensors| jumps_by_table(*table, sensors)))
+        })
+        .collect();
+    if acceptable.is_empty() {
+        return None;
+    }
+
+    let candidates = walk_instructions();
+    let start = (0 as TruthTable, 0 as TruthTable);
+    let mut previous: HashMap<(TruthTable, TruthTable), (Instruction, (TruthTable, TruthTable))> =
+        HashMap::new();
+    let mut queue = VecDeque::new();
+    queue.push_back((start, 0));
+
+    while let Some((state, length)) = queue.pop_front() {
+        if acceptable.contains(&state.1) {
+            let mut instructions = Vec::new();
+            let mut at = state;
+            while at != start {
+                let (instruction, before) = previous[&at];
+                instructions.push(instruction);
+                at = before;
+            }
+            instructions.reverse();
+            let script = Script::new(instructions, Mode::Walk).ok()?;
+            debug_assert!(hulls
+                .iter()
+                .all(|hull| survives(hull, |sensors| script.jumps(sensors))));
+            return Some(script);
+        }
+        if length == MAX_INSTRUCTIONS {
+            continue;
+        }
+
+        for instruction in candidates.iter() {
+            let next = apply(*instruction, state);
+            if next != start && !previous.contains_key(&next) {
+                previous.insert(next, (*instruction, state));
+                queue.push_back((next, length + 1));
+            }
+        }
+    }
+
+    None
+}
+
+fn jumps_by_table(table: TruthTable, sensors: &[bool]) -> bool {
+    let index = (0..WALK_SENSORS)
+        .filter(|s| sensors[*s])
+        .fold(0, |index, s| index | (1 << s));
+    table & (1 << index) != 0
+}
+
+fn walk_instructions() -> Vec<Instruction> {
+    let sources = SENSORS[..WALK_SENSORS]
+        .iter()
+        .chain([Register::T, Register::J].iter());
+    let mut instructions = Vec::new();
+    for from in sources {
+        for to in [Register::T, Register::J] {
+            for op in [Op::And, Op::Or, Op::Not] {
+                instructions.push(Instruction {
+                    op,
+                    from: *from,
+                    to,
+                });
+            }
+        }
+    }
+    instructions
+}
+
+// The truth table for a sensor is set for each combination where that sensor sees ground
+fn sensor_table(sensor: usize) -> TruthTable {
+    (0..16)
+        .filter(|index| index & (1 << sensor) != 0)
+        .fold(0, |table, index| table | (1 << index))
+}
+
+fn apply(instruction: Instruction, (t, j): (TruthTable, TruthTable)) -> (TruthTable, TruthTable) {
+    let from = match instruction.from {
+        Register::T => t,
+        Register::J => j,
+        sensor => sensor_table(SENSORS.iter().position(|s| *s == sensor).unwrap()),
+    };
+    let to = if instruction.to == Register::T { t } else { j };
+    let result = match instruction.op {
+        Op::And => to & from,
+        Op::Or => to | from,
+        Op::Not => !from,
+    };
+    if instruction.to == Register::T {
+        (result, j)
+    } else {
+        (t, result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hull(s: &str) -> Hull {
+        parse_hull(s).unwrap()
+    }
+
+    const ANIMATION: &str = "
+Input instructions:
+
+Walking...
+
+
+Didn't make it across:
+
+.................
+.................
+@................
+#####.#..########
+
+.................
+.................
+.@...............
+#####.#..########
+";
+
+    #[test]
+    fn reads_the_hull_from_the_animation() {
+        assert_eq!(hull("#####.#..########"), parse_hull(ANIMATION).unwrap());
+        assert_eq!(None, parse_hull("Walking...\n"));
+    }
+
+    #[test]
+    fn droid_falls_in_holes() {
+        let never = |_: &[bool]| false;
+        let always = |_: &[bool]| true;
+        assert!(survives(&hull("#########"), never));
+        assert!(!survives(&hull("#####.###"), never));
+        assert!(survives(&hull("#####.###"), always));
+        assert!(!survives(&hull("####.####"), always));
+    }
+
+    #[test]
+    fn finds_walk_scripts() {
+        // Nothing to avoid, so no need to jump
+        assert_eq!(0, find_walk_script(&[]).unwrap().instructions().len());
+
+        let hulls = vec![
+            hull("#####.###########"),
+            hull("#####..#.########"),
+            hull("#####...#########"),
+            hull("#####.#..########"),
+            hull("#####.##.##.#####"),
+        ];
+        let script = find_walk_script(&hulls).unwrap();
+        for hull in hulls.iter() {
+            assert!(survives(hull, |sensors| script.jumps(sensors)));
+        }
+    }
+
+    #[test]
+    fn some_hulls_cannot_be_crossed() {
+        assert!(find_walk_script(&[hull("#####....#####")]).is_none());
+    }
+}
@@ -0,0 +1,250 @@
+use std::fmt;
+
+// The springdroid only has room for this many instructions
+pub(super) const MAX_INSTRUCTIONS: usize = 15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) enum Register {
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+    I,
+    T,
+    J,
+}
+
+pub(super) const SENSORS: [Register; 9] = [
+    Register::A,
+    Register::B,
+    Register::C,
+    Register::D,
+    Register::E,
+    Register::F,
+    Register::G,
+    Register::H,
+    Register::I,
+];
+
+impl Register {
+    // How many tiles ahead the register looks, counting from zero
+    fn sensor(&self) -> Option<usize> {
+        SENSORS.iter().position(|s| s == self)
+    }
+
+    fn is_writable(&self) -> bool {
+        matches!(self, Register::T | Register::J)
+    }
+}
+
+impl fmt::Display for Register {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) enum Op {
+    And,
+    Or,
+    Not,
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Op::And => write!(f, "AND"),
+            Op::Or => write!(f, "OR"),
+            Op::Not => write!(f, "NOT"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) struct Instruction {
+    pub(super) op: Op,
+    pub(super) from: Register,
+    pub(super) to: Register,
+}
+
+impl Instruction {
+    pub(super) fn and(from: Register, to: Register) -> Instruction {
+        Instruction {
+            op: Op::And,
+            from,
+            to,
+        }
+    }
+
+    pub(super) fn or(from: Register, to: Register) -> Instruction {
+        Instruction {
+            op: Op::Or,
+            from,
+            to,
+        }
+    }
+
+    pub(super) fn not(from: Register, to: Register) -> Instruction {
+        Instruction {
+            op: Op::Not,
+            from,
+            to,
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.op, self.from, self.to)
+    }
+}
+
+// WALK mode can only see four tiles ahead, RUN mode can see nine
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum Mode {
+    Walk,
+    Run,
+}
+
+impl Mode {
+    pub(super) fn sensors(&self) -> usize {
+        match self {
+            Mode::Walk => 4,
+            Mode::Run => 9,
+        }
+    }
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mode::Walk => write!(f, "WALK"),
+            Mode::Run => write!(f, "RUN"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub(super) enum ScriptError {
+    TooManyInstructions(usize),
+    SensorOutOfRange { index: usize },
+    NotWritable { index: usize },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Script {
+    instructions: Vec<Instruction>,
+    mode: Mode,
+}
+
+impl Script {
+    pub(super) fn new(instructions: Vec<Instruction>, mode: Mode) -> Result<Script, ScriptError> {
+        if instructions.len() > MAX_INSTRUCTIONS {
+            return Err(ScriptError::TooManyInstructions(instructions.len()));
+        }
+
+        for (index, instruction) in instructions.iter().enumerate() {
+            if !instruction.to.is_writable() {
+                return Err(ScriptError::NotWritable { index });
+            }
+            if let Some(sensor) = instruction.from.sensor() {
+                if sensor >= mode.sensors() {
+                    return Err(ScriptError::SensorOutOfRange { index });
+                }
+            }
+        }
+
+        Ok(Script { instructions, mode })
+    }
+
+    #[cfg(test)]
+    pub(super) fn instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
+
+    // The lines of text the droid expects: each instruction, then the mode to start it in
+    pub(super) fn lines(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self.instructions.iter().map(|i| i.to_string()).collect();
+        lines.push(self.mode.to_string());
+        lines
+    }
+
+    // Run the script against what the sensors can see, where true is ground
+    pub(super) fn jumps(&self, sensors: &[bool]) -> bool {
+        let mut t = false;
+        let mut j = false;
+        for instruction in self.instructions.iter() {
+            let from = match instruction.from {
+                Register::T => t,
+                Register::J => j,
+                sensor => sensors[sensor.sensor().unwrap()],
+            };
+            let to = match instruction.to {
+                Register::T => &mut t,
+                _ => &mut j,
+            };
+            *to = match instruction.op {
+                Op::And => *to && from,
+                Op::Or => *to || from,
+                Op::Not => !from,
+            };
+        }
+        j
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Register::*;
+
+    #[test]
+    fn checks_the_droid_limits() {
+        let too_long = vec![Instruction::not(A, J); 16];
+        assert_eq!(
+            Err(ScriptError::TooManyInstructions(16)),
+            Script::new(too_long, Mode::Run)
+        );
+
+        let to_sensor = vec![Instruction::not(A, J), Instruction::or(T, B)];
+        assert_eq!(
+            Err(ScriptError::NotWritable { index: 1 }),
+            Script::new(to_sensor, Mode::Walk)
+        );
+
+        let too_far = vec![Instruction::not(E, J)];
+        assert_eq!(
+            Err(ScriptError::SensorOutOfRange { index: 0 }),
+            Script::new(too_far.clone(), Mode::Walk)
+        );
+        assert!(Script::new(too_far, Mode::Run).is_ok());
+    }
+
+    #[test]
+    fn writes_and_evaluates_scripts() {
+        // Jump if there's a hole ahead, and ground to land on
+        let script = Script::new(
+            vec![
+                Instruction::not(A, J),
+                Instruction::not(C, T),
+                Instruction::or(T, J),
+                Instruction::and(D, J),
+            ],
+            Mode::Walk,
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec!["NOT A J", "NOT C T", "OR T J", "AND D J", "WALK"],
+            script.lines()
+        );
+        assert!(script.jumps(&[false, true, true, true]));
+        assert!(script.jumps(&[true, true, false, true]));
+        assert!(!script.jumps(&[true, false, true, true]));
+        assert!(!script.jumps(&[false, true, true, false]));
+    }
+}
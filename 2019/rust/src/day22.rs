@@ -0,0 +1,247 @@
+use crate::solution::{self, Skipped, Solution};
+use num::integer::Integer;
+
+const INPUT_PATH: &str = "inputs/day22.txt";
+
+const SMALL_DECK: i128 = 10007;
+const HUGE_DECK: i128 = 119315717514047;
+const REPETITIONS: u64 = 101741582076661;
+
+pub fn run_day_twenty_two() {
+    solution::run::<Solver>();
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2019;
+    const DAY: u32 = 22;
+
+    type Input = Vec<Technique>;
+    type PartOne = i128;
+    type PartTwo = i128;
+
+    fn parse() -> Result<Vec<Technique>, Skipped> {
+        Ok(parse_techniques(&solution::read_input(INPUT_PATH)?))
+    }
+
+    fn part_one(input: &Vec<Technique>) -> i128 {
+        compose(input, SMALL_DECK).apply(2019)
+    }
+
+    fn part_two(input: &Vec<Technique>) -> i128 {
+        // Work backwards from the position, to find the card that ends up there
+        let shuffle = compose(input, HUGE_DECK)
+            .pow(REPETITIONS)
+            .inverse()
+            .expect("The deck size is prime");
+        shuffle.apply(2020)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Technique {
+    NewStack,
+    Cut(i128),
+    Increment(i128),
+}
+
+impl Technique {
+    fn parse(line: &str) -> Option<Technique> {
+        if line == "deal into new stack" {
+            return Some(Technique::NewStack);
+        }
+        if let Some(n) = line.strip_prefix("cut ") {
+            return n.parse().ok().map(Technique::Cut);
+        }
+        if let Some(n) = line.strip_prefix("deal with increment ") {
+            return n.parse().ok().map(Technique::Increment);
+        }
+        None
+    }
+
+    // Where the card at position x ends up
+    fn to_map(self, size: i128) -> LinearMap {
+        match self {
+            Technique::NewStack => LinearMap::new(-1, -1, size),
+            Technique::Cut(n) => LinearMap::new(1, -n, size),
+            Technique::Increment(n) => LinearMap::new(n, 0, size),
+        }
+    }
+}
+
+fn parse_techniques(s: &str) -> Vec<Technique> {
+    s.lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty())
+        .map(|l| Technique::parse(l).unwrap_or_else(|| panic!("Unknown technique: {}", l)))
+        .collect()
+}
+
+fn compose(techniques: &[Technique], size: i128) -> LinearMap {
+    techniques.iter().fold(LinearMap::identity(size), |map, t| {
+        map.then(&t.to_map(size))
+    })
+}
+
+// x -> a*x + b, mod m. Everything stays below m, so the products fit in an i128
+// for any deck size that fits in an i64.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct LinearMap {
+    a: i128,
+    b: i128,
+    m: i128,
+}
+
+impl LinearMap {
+    fn new(a: i128, b: i128, m: i128) -> LinearMap {
+        LinearMap {
+            a: a.mod_floor(&m),
+            b: b.mod_floor(&m),
+            m,
+        }
+    }
+
+    fn identity(m: i128) -> LinearMap {
+        LinearMap::new(1, 0, m)
+    }
+
+    fn apply(&self, x: i128) -> i128 {
+        (self.a * x + self.b).mod_floor(&self.m)
+    }
+
+    // This map, followed by the other one
+    fn then(&self, other: &LinearMap) -> LinearMap {
+        LinearMap::new(other.a * self.a, other.a * self.b + other.b, self.m)
+    }
+
+    // The map applied n times over, by repeated squaring
+    fn pow(&self, mut n: u64) -> LinearMap {
+        let mut result = LinearMap::identity(self.m);
+        let mut square = *self;
+        while n > 0 {
+            if n & 1 == 1 {
+                result = result.then(&square);
+            }
+            square = square.then(&square);
+            n >>= 1;
+        }
+        result
+    }
+
+    // x = (y - b) / a, which needs a to have an inverse mod m
+    fn inverse(&self) -> Option<LinearMap> {
+        let gcd = self.a.extended_gcd(&self.m);
+        if gcd.gcd != 1 {
+            return None;
+        }
+        let a = gcd.x;
+        Some(LinearMap::new(a, -a * self.b, self.m))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Shuffle an actual deck of cards, returning the card at each position
+    fn simulate(techniques: &[Technique], size: usize, deck: &[usize]) -> Vec<usize> {
+        let mut deck = deck.to_vec();
+        for technique in techniques {
+            deck = match *technique {
+                Technique::NewStack => deck.iter().rev().copied().collect(),
+                Technique::Cut(n) => {
+                    let n = n.rem_euclid(size as i128) as usize;
+                    deck[n..].iter().chain(deck[..n].iter()).copied().collect()
+                }
+                Technique::Increment(n) => {
+                    let mut dealt = vec![0; size];
+                    for (i, card) in deck.iter().enumerate() {
+                        dealt[(i * n as usize) % size] = *card;
+                    }
+                    dealt
+                }
+            };
+        }
+        deck
+    }
+
+    const EXAMPLES: [(&str, [usize; 10]); 4] = [
+        (
+            "deal with increment 7
+            deal into new stack
+            deal into new stack",
+            [0, 3, 6, 9, 2, 5, 8, 1, 4, 7],
+        ),
+        (
+            "cut 6
+            deal with increment 7
+            deal into new stack",
+            [3, 0, 7, 4, 1, 8, 5, 2, 9, 6],
+        ),
+        (
+            "deal with increment 7
+            deal with increment 9
+            cut -2",
+            [6, 3, 0, 7, 4, 1, 8, 5, 2, 9],
+        ),
+        (
+            "deal into new stack
+            cut -2
+            deal with increment 7
+            cut 8
+            cut -4
+            deal with increment 7
+            cut 3
+            deal with increment 9
+            deal with increment 3
+            cut -1",
+            [9, 2, 5, 8, 1, 4, 7, 0, 3, 6],
+        ),
+    ];
+
+    #[test]
+    fn examples_match_the_simulation() {
+        let fresh: Vec<usize> = (0..10).collect();
+        for (text, expected) in EXAMPLES.iter() {
+            let techniques = parse_techniques(text);
+            assert_eq!(expected.to_vec(), simulate(&techniques, 10, &fresh));
+
+            let shuffle = compose(&techniques, 10);
+            for (position, card) in expected.iter().enumerate() {
+                assert_eq!(position as i128, shuffle.apply(*card as i128));
+            }
+        }
+    }
+
+    #[test]
+    fn repeated_and_inverted_shuffles_match_the_simulation() {
+        // A prime sized deck, so that every increment can be undone
+        let size = 37;
+        let techniques = parse_techniques(EXAMPLES[3].0);
+        let shuffle = compose(&techniques, size as i128);
+
+        let mut deck: Vec<usize> = (0..size).collect();
+        for repetitions in 1..=50 {
+            deck = simulate(&techniques, size, &deck);
+            let inverse = shuffle.pow(repetitions).inverse().unwrap();
+            for (position, card) in deck.iter().enumerate() {
+                assert_eq!(*card as i128, inverse.apply(position as i128));
+            }
+        }
+    }
+
+    #[test]
+    fn huge_powers_wrap_around() {
+        // Shuffling a prime sized deck m-1 times over with increments leaves it as it started
+        let shuffle = compose(&parse_techniques("deal with increment 3"), HUGE_DECK);
+        assert_eq!(
+            LinearMap::identity(HUGE_DECK),
+            shuffle.pow(HUGE_DECK as u64 - 1)
+        );
+        assert_eq!(
+            None,
+            compose(&parse_techniques("deal with increment 5"), 10).inverse()
+        );
+    }
+}
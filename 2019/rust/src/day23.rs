@@ -0,0 +1,156 @@
+use crate::intcode::{Gateway, IntCodeResult, MemoryCell, Network};
+use crate::solution::{self, Skipped, Solution};
+
+const INPUT_PATH: &str = "inputs/day23.txt";
+const NETWORK_SIZE: usize = 50;
+const NAT_ADDRESS: usize = 255;
+
+// Every packet is an address, then X and Y
+const PACKET_SIZE: usize = 3;
+
+pub fn run_day_twenty_three() {
+    solution::run::<Solver>();
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2019;
+    const DAY: u32 = 23;
+
+    // Both parts come from the same run of the network
+    type Input = NatReport;
+    type PartOne = MemoryCell;
+    type PartTwo = MemoryCell;
+
+    fn parse() -> Result<NatReport, Skipped> {
+        let program: Vec<MemoryCell> =
+            crate::util::parse_int_array(&solution::read_input(INPUT_PATH)?);
+        Ok(run_network(&program, NETWORK_SIZE).expect("The network ran"))
+    }
+
+    fn part_one(report: &NatReport) -> MemoryCell {
+        report.first_to_nat
+    }
+
+    fn part_two(report: &NatReport) -> MemoryCell {
+        report.first_repeated
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct NatReport {
+    // The Y value of the first packet sent to the NAT
+    first_to_nat: MemoryCell,
+    // The first Y value the NAT delivers to address 0 twice in a row
+    first_repeated: MemoryCell,
+}
+
+// Sits outside the network at address 255, and wakes address 0 whenever the network is idle
+#[derive(Default)]
+struct Nat {
+    last_packet: Option<(MemoryCell, MemoryCell)>,
+    first_to_nat: Option<MemoryCell>,
+    last_delivered: Option<MemoryCell>,
+    first_repeated: Option<MemoryCell>,
+}
+
+impl Gateway for Nat {
+    fn receive(&mut self, packet: &[MemoryCell]) {
+        if packet[0] != NAT_ADDRESS as MemoryCell {
+            panic!("No NIC at address {}", packet[0]);
+        }
+        self.last_packet = Some((packet[1], packet[2]));
+        self.first_to_nat.get_or_insert(packet[2]);
+    }
+
+    fn idle(&mut self) -> Option<Vec<MemoryCell>> {
+        let (x, y) = self
+            .last_packet
+            .expect("The network went idle before anything was sent to the NAT");
+        if self.last_delivered == Some(y) {
+            self.first_repeated = Some(y);
+            return None;
+        }
+        self.last_delivered = Some(y);
+        Some(vec![0, x, y])
+    }
+}
+
+// Each NIC runs on its own thread, and the network sends packets between them by address
+fn run_network(program: &[MemoryCell], size: usize) -> IntCodeResult<NatReport> {
+    let mut network = Network::new();
+    for address in 0..size {
+        let name = format!("nic{}", address);
+        network
+            .add_node(&name, program)
+            .and_then(|_| network.queue(&name, &[address as MemoryCell]))
+            .expect("Every NIC has its own name");
+    }
+    network.route_packets(PACKET_SIZE);
+
+    let mut nat = Nat::default();
+    network.run_with_gateway(&mut nat)?;
+    Ok(NatReport {
+        first_to_nat: nat.first_to_nat.expect("Something was sent to the NAT"),
+        first_repeated: nat.first_repeated.expect("The NAT repeated itself"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intcode::assemble;
+
+    // A stand in for the puzzle's NIC. Each one passes packets on to the next address.
+    // Address 0 starts things off with a Y of 3, and the last address sends packets to
+    // the NAT with Y one bigger, until it reaches 20.
+    fn chain(size: usize) -> Vec<MemoryCell> {
+        let source = format!(
+            "
+                    in   [address]
+                    add  [address], #1, [next]
+                    eq   [next], #{}, [last]
+                    jt   [address], #poll
+                    out  #1
+                    out  #0
+                    out  #3
+            poll:   in   [x]
+                    eq   [x], #-1, [empty]
+                    jt   [empty], #poll
+                    in   [y]
+                    jf   [last], #send
+                    lt   [y], #20, [less]
+                    add  [y], [less], [y]
+                    add  #255, #0, [next]
+            send:   out  [next]
+                    out  [x]
+                    out  [y]
+                    jt   #1, #poll
+            address: data 0
+            next:   data 0
+            last:   data 0
+            x:      data 0
+            y:      data 0
+            empty:  data 0
+            less:   data 0
+            ",
+            size
+        );
+        assemble(&source).unwrap()
+    }
+
+    #[test]
+    fn nat_wakes_the_network_until_it_repeats_itself() {
+        for size in [2, 5, 50] {
+            let report = run_network(&chain(size), size).unwrap();
+            assert_eq!(
+                NatReport {
+                    first_to_nat: 4,
+                    first_repeated: 20
+                },
+                report
+            );
+        }
+    }
+}
@@ -0,0 +1,287 @@
+use crate::solution::{self, Skipped, Solution};
+use std::collections::{HashMap, HashSet};
+
+const INPUT_PATH: &str = "inputs/day24.txt";
+const SIZE: usize = 5;
+const TILES: usize = SIZE * SIZE;
+const CENTRE: usize = TILES / 2;
+const MINUTES: usize = 200;
+
+// One bit for each tile, reading left to right and top to bottom, which is also its biodiversity
+type Layout = u32;
+
+// Zero is the starting level, and each level inside the centre tile is one deeper
+type Level = i32;
+
+pub fn run_day_twenty_four() {
+    solution::run::<Solver>();
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2019;
+    const DAY: u32 = 24;
+
+    type Input = Layout;
+    type PartOne = Layout;
+    type PartTwo = u32;
+
+    fn parse() -> Result<Layout, Skipped> {
+        Ok(parse_layout(&solution::read_input(INPUT_PATH)?))
+    }
+
+    fn part_one(input: &Layout) -> Layout {
+        first_repeat(*input)
+    }
+
+    fn part_two(input: &Layout) -> u32 {
+        count_bugs(&run_recursive(*input, MINUTES))
+    }
+}
+
+fn parse_layout(s: &str) -> Layout {
+    s.chars()
+        .filter(|c| *c == '#' || *c == '.' || *c == '?')
+        .enumerate()
+        .filter(|(_, c)| *c == '#')
+        .fold(0, |layout, (idx, _)| layout | (1 << idx))
+}
+
+fn has_bug(layout: Layout, idx: usize) -> bool {
+    layout & (1 << idx) != 0
+}
+
+// A bug survives with exactly one neighbour, and an empty tile is infested by one or two
+fn next_tile(is_bug: bool, neighbours: usize) -> bool {
+    neighbours == 1 || (!is_bug && neighbours == 2)
+}
+
+// The neighbours on a single grid, without any levels
+fn flat_neighbours(idx: usize) -> Vec<usize> {
+    let (x, y) = (idx % SIZE, idx / SIZE);
+    let mut found = Vec::with_capacity(4);
+    if y > 0 {
+        found.push(idx - SIZE);
+    }
+    if x > 0 {
+        found.push(idx - 1);
+    }
+    if x + 1 < SIZE {
+        found.push(idx + 1);
+    }
+    if y + 1 < SIZE {
+        found.push(idx + SIZE);
+    }
+    found
+}
+
+fn step_flat(layout: Layout) -> Layout {
+    (0..TILES)
+        .filter(|idx| {
+            let neighbours = flat_neighbours(*idx)
+                .iter()
+                .filter(|n| has_bug(layout, **n))
+                .count();
+            next_tile(has_bug(layout, *idx), neighbours)
+        })
+        .fold(0, |next, idx| next | (1 << idx))
+}
+
+fn first_repeat(mut layout: Layout) -> Layout {
+    let mut seen = HashSet::new();
+    while seen.insert(layout) {
+        layout = step_flat(layout);
+    }
+    layout
+}
+
+// The neighbours of a tile across the levels. The edges of a level touch the tiles around the
+// centre of the level outside it, and the tiles around the centre touch a whole edge of the
+// level inside.
+fn recursive_neighbours(level: Level, idx: usize) -> Vec<(Level, usize)> {
+    let (x, y) = (idx % SIZE, idx / SIZE);
+    let mut found = Vec::with_capacity(8);
+    let edge = |pick: fn(usize) -> usize| (0..SIZE).map(move |i| (level + 1, pick(i)));
+
+    for n in flat_neighbours(idx) {
+        if n != CENTRE {
+            found.push((level, n));
+        }
+    }
+
+    // Above, left, right and below, stepping out a level past the edge
+    if y == 0 {
+        found.push((level - 1, CENTRE - SIZE));
+    }
+    if x == 0 {
+        found.push((level - 1, CENTRE - 1));
+    }
+    if x + 1 == SIZE {
+        found.push((level - 1, CENTRE + 1));
+    }
+    if y + 1 == SIZE {
+        found.push((level - 1, CENTRE + SIZE));
+    }
+
+    // Stepping in a level, on to the edge facing this tile
+    if idx == CENTRE - SIZE {
+        found.extend(edge(|i| i));
+    }
+    if idx == CENTRE - 1 {
+        found.extend(edge(|i| i * SIZE));
+    }
+    if idx == CENTRE + 1 {
+        found.extend(edge(|i| i * SIZE + SIZE - 1));
+    }
+    if idx == CENTRE + SIZE {
+        found.extend(edge(|i| (SIZE - 1) * SIZE + i));
+    }
+
+    found
+}
+
+fn step_recursive(levels: &HashMap<Level, Layout>) -> HashMap<Level, Layout> {
+    let lowest = levels.keys().min().copied().unwrap_or(0) - 1;
+    let highest = levels.keys().max().copied().unwrap_or(0) + 1;
+    let get = |level: Level| levels.get(&level).copied().unwrap_or(0);
+
+    let mut next = HashMap::new();
+    for level in lowest..=highest {
+        let layout = (0..TILES)
+            .filter(|idx| *idx != CENTRE)
+            .filter(|idx| {
+                let neighbours = recursive_neighbours(level, *idx)
+                    .iter()
+                    .filter(|(l, n)| has_bug(get(*l), *n))
+                    .count();
+                next_tile(has_bug(get(level), *idx), neighbours)
+            })
+            .fold(0, |next, idx| next | (1 << idx));
+
+        if layout != 0 {
+            next.insert(level, layout);
+        }
+    }
+    next
+}
+
+fn run_recursive(layout: Layout, minutes: usize) -> HashMap<Level, Layout> {
+    let mut levels = HashMap::new();
+    levels.insert(0, layout & !(1 << CENTRE));
+    for _ in 0..minutes {
+        levels = step_recursive(&levels);
+    }
+    levels
+}
+
+fn count_bugs(levels: &HashMap<Level, Layout>) -> u32 {
+    levels.values().map(|layout| layout.count_ones()).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "
+        ....#
+        #..#.
+        #..##
+        ..#..
+        #....";
+
+    #[test]
+    fn first_repeat_example() {
+        let repeat = first_repeat(parse_layout(EXAMPLE));
+        let expected = parse_layout(
+            "
+            .....
+            .....
+            .....
+            #....
+            .#...",
+        );
+        assert_eq!(expected, repeat);
+        assert_eq!(2129920, repeat);
+    }
+
+    // The worked example numbers the tiles of one level from 1 to 25,
+    // and names the tiles of the level inside it from A to Y
+    fn tile(name: &str) -> (Level, usize) {
+        match name.parse::<usize>() {
+            Ok(n) => (0, n - 1),
+            Err(_) => (1, (name.as_bytes()[0] - b'A') as usize),
+        }
+    }
+
+    fn neighbours_of(name: &str) -> Vec<(Level, usize)> {
+        let (level, idx) = tile(name);
+        let mut found = recursive_neighbours(level, idx);
+        found.sort();
+        found
+    }
+
+    fn tiles(names: &[&str]) -> Vec<(Level, usize)> {
+        let mut found: Vec<(Level, usize)> = names.iter().map(|n| tile(n)).collect();
+        found.sort();
+        found
+    }
+
+    #[test]
+    fn recursive_neighbours_example() {
+        assert_eq!(tiles(&["14", "18", "20", "24"]), neighbours_of("19"));
+        assert_eq!(tiles(&["B", "F", "H", "L"]), neighbours_of("G"));
+        assert_eq!(tiles(&["8", "C", "E", "I"]), neighbours_of("D"));
+        assert_eq!(tiles(&["8", "D", "14", "J"]), neighbours_of("E"));
+        assert_eq!(
+            tiles(&["9", "E", "J", "O", "T", "Y", "15", "19"]),
+            neighbours_of("14")
+        );
+
+        // N also touches the whole right hand edge of the level inside it
+        let mut expected = tiles(&["I", "O", "S"]);
+        expected.extend([4, 9, 14, 19, 24].iter().map(|idx| (2, *idx)));
+        expected.sort();
+        assert_eq!(expected, neighbours_of("N"));
+    }
+
+    #[test]
+    fn recursive_example() {
+        let levels = run_recursive(parse_layout(EXAMPLE), 10);
+        assert_eq!(99, count_bugs(&levels));
+        assert_eq!(11, levels.len());
+        assert_eq!(
+            parse_layout(
+                "
+                .#...
+                .#.##
+                .#?..
+                .....
+                ....."
+            ),
+            levels[&0]
+        );
+        assert_eq!(
+            parse_layout(
+                "
+                ..#..
+                .#.#.
+                ..?.#
+                .#.#.
+                ..#.."
+            ),
+            levels[&-5]
+        );
+        assert_eq!(
+            parse_layout(
+                "
+                ####.
+                #..#.
+                #.?#.
+                ####.
+                ....."
+            ),
+            levels[&5]
+        );
+    }
+}
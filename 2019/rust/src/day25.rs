@@ -0,0 +1,492 @@
+use crate::intcode::{AsciiInput, AsciiOutput, Computer, MemoryCell, PagedStorage};
+use crate::solution::{self, NoPartTwo, Skipped, Solution};
+use regex::Regex;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+const INPUT_PATH: &str = "inputs/day25.txt";
+const CHECKPOINT: &str = "Security Checkpoint";
+
+// Picking up any of these ends the game, or leaves the droid stuck
+const DEADLY_ITEMS: [&str; 5] = [
+    "escape pod",
+    "giant electromagnet",
+    "infinite loop",
+    "molten lava",
+    "photons",
+];
+
+lazy_static! {
+    static ref PASSWORD_PATTERN: Regex = Regex::new(r"typing (\d+) on the keypad").unwrap();
+}
+
+pub fn run_day_twenty_five() {
+    solution::run::<Solver>();
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2019;
+    const DAY: u32 = 25;
+
+    type Input = Vec<MemoryCell>;
+    type PartOne = String;
+    type PartTwo = NoPartTwo;
+
+    fn parse() -> Result<Vec<MemoryCell>, Skipped> {
+        Ok(crate::util::parse_int_array(&solution::read_input(
+            INPUT_PATH,
+        )?))
+    }
+
+    fn part_one(input: &Vec<MemoryCell>) -> String {
+        solve(&mut Adventure::new(input)).expect("Made it through the checkpoint")
+    }
+
+    fn part_two(_: &Vec<MemoryCell>) -> NoPartTwo {
+        NoPartTwo
+    }
+}
+
+// Something that plays the text adventure, a line at a time
+pub trait Terminal {
+    // Everything the ship printed since the last command
+    fn read(&mut self) -> String;
+
+    fn write(&mut self, command: &str);
+
+    fn send(&mut self, command: &str) -> String {
+        self.write(command);
+        self.read()
+    }
+}
+
+// The droid, running the puzzle's Intcode program
+pub struct Adventure {
+    computer: Computer<PagedStorage, AsciiInput, AsciiOutput>,
+}
+
+impl Adventure {
+    pub fn new(program: &[MemoryCell]) -> Adventure {
+        Adventure {
+            computer: Computer::new(
+                PagedStorage::from(program),
+                AsciiInput::new(),
+                AsciiOutput::new(),
+            ),
+        }
+    }
+
+    pub fn load() -> Option<Adventure> {
+        let text = crate::util::try_read_file(INPUT_PATH)?;
+        Some(Adventure::new(&crate::util::parse_int_array(&text)))
+    }
+
+    pub fn has_ended(&self) -> bool {
+        self.computer.has_halted()
+    }
+
+    // Commands typed in by a player need checking first, as the droid only reads ASCII
+    pub fn try_write(&mut self, command: &str) -> Result<(), NotAscii> {
+        if !command.is_ascii() {
+            return Err(NotAscii);
+        }
+        self.write(command);
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct NotAscii;
+
+impl Terminal for Adventure {
+    fn read(&mut self) -> String {
+        if !self.computer.has_halted() {
+            self.computer.resume().expect("The droid is still running");
+        }
+        self.computer.output().take_text()
+    }
+
+    fn write(&mut self, command: &str) {
+        self.computer.input().queue_line(command);
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Room {
+    name: String,
+    doors: Vec<String>,
+    items: Vec<String>,
+}
+
+// Sometimes more than one room is described, like when the droid is thrown out of somewhere,
+// so it's the last one which says where the droid is now
+fn parse_room(text: &str) -> Option<Room> {
+    let start = text.rfind("== ")?;
+    let mut lines = text[start..].lines();
+    let name = lines.next()?.trim_matches(|c| c == '=' || c == ' ');
+
+    let mut room = Room {
+        name: name.to_string(),
+        doors: Vec::new(),
+        items: Vec::new(),
+    };
+    let mut section = None;
+    for line in lines {
+        if line.starts_with("Doors here lead") {
+            section = Some(&mut room.doors);
+        } else if line.starts_with("Items here") {
+            section = Some(&mut room.items);
+        } else if let (Some(list), Some(entry)) = (section.as_mut(), line.strip_prefix("- ")) {
+            list.push(entry.to_string());
+        } else {
+            section = None;
+        }
+    }
+    Some(room)
+}
+
+fn password(text: &str) -> Option<String> {
+    let captures = PASSWORD_PATTERN.captures(text)?;
+    Some(captures[1].to_string())
+}
+
+fn opposite(door: &str) -> &'static str {
+    match door {
+        "north" => "south",
+        "south" => "north",
+        "east" => "west",
+        "west" => "east",
+        _ => panic!("Unexpected door: {}", door),
+    }
+}
+
+struct Explorer<'a, T: Terminal> {
+    terminal: &'a mut T,
+    // Which room each door leads to, for every room that's been visited
+    map: HashMap<String, HashMap<String, String>>,
+    inventory: Vec<String>,
+    // The door out of the checkpoint on to the pressure sensitive floor
+    plate_door: Option<String>,
+}
+
+impl<'a, T: Terminal> Explorer<'a, T> {
+    // Walk every door depth first, picking up anything safe, and come back the same way.
+    // The way past the checkpoint is only noted, as the floor would just throw the droid back.
+    fn explore(&mut self, room: &Room, came_through: Option<&str>) -> Option<()> {
+        self.map.entry(room.name.clone()).or_default();
+        for item in room.items.iter() {
+            if !DEADLY_ITEMS.contains(&item.as_str()) {
+                self.terminal.send(&format!("take {}", item));
+                self.inventory.push(item.clone());
+            }
+        }
+
+        for door in room.doors.iter() {
+            if Some(door.as_str()) == came_through {
+                continue;
+            }
+            if room.name == CHECKPOINT {
+                self.plate_door = Some(door.clone());
+                continue;
+            }
+
+            let next = parse_room(&self.terminal.send(door))?;
+            let back = opposite(door);
+            self.map
+                .entry(room.name.clone())
+                .or_default()
+                .insert(door.clone(), next.name.clone());
+            let is_new = !self.map.contains_key(&next.name);
+            self.map
+                .entry(next.name.clone())
+                .or_default()
+                .insert(back.to_string(), room.name.clone());
+
+            if is_new {
+                self.explore(&next, Some(back))?;
+            }
+            self.terminal.send(back);
+        }
+        Some(())
+    }
+
+    // The doors to walk through to get from one room to another
+    fn route(&self, from: &str, to: &str) -> Option<Vec<String>> {
+        let mut previous: HashMap<&str, (&str, &str)> = HashMap::new();
+        let mut seen: HashSet<&str> = HashSet::new();
+        let mut queue = VecDeque::new();
+        seen.insert(from);
+        queue.push_back(from);
+
+        while let Some(room) = queue.pop_front() {
+            if room == to {
+                let mut doors = Vec::new();
+                let mut at = room;
+                while let Some((before, door)) = previous.get(at) {
+                    doors.push(door.to_string());
+                    at = before;
+                }
+                doors.reverse();
+                return Some(doors);
+            }
+            for (door, next) in self.map.get(room)?.iter() {
+                if seen.insert(next) {
+                    previous.insert(next, (room, door));
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    // Try carrying each combination of items on to the floor. Anything heavier than a set
+    // that was too heavy, or lighter than a set that was too light, can be skipped.
+    fn pass_checkpoint(&mut self) -> Option<String> {
+        let plate_door = self.plate_door.clone()?;
+        let items = self.inventory.clone();
+        let mut holding: Vec<bool> = vec![true; items.len()];
+        let mut too_heavy: Vec<u32> = Vec::new();
+        let mut too_light: Vec<u32> = Vec::new();
+
+        for set in 0..(1u32 << items.len()) {
+            if too_heavy.iter().any(|h| set & h == *h) || too_light.iter().any(|l| set & l == set) {
+                continue;
+            }
+
+            for (idx, item) in items.iter().enumerate() {
+                let wanted = set & (1 << idx) != 0;
+                if wanted != holding[idx] {
+                    let verb = if wanted { "take" } else { "drop" };
+                    self.terminal.send(&format!("{} {}", verb, item));
+                    holding[idx] = wanted;
+                }
+            }
+
+            let response = self.terminal.send(&plate_door);
+            if let Some(password) = password(&response) {
+                return Some(password);
+            } else if response.contains("lighter than the detected value") {
+                too_heavy.push(set);
+            } else if response.contains("heavier than the detected value") {
+                too_light.push(set);
+            }
+        }
+        None
+    }
+}
+
+// Explore the ship, take everything safe to the checkpoint, and find the right weight
+pub fn solve<T: Terminal>(terminal: &mut T) -> Option<String> {
+    let start = parse_room(&terminal.read())?;
+    let mut explorer = Explorer {
+        terminal,
+        map: HashMap::new(),
+        inventory: Vec::new(),
+        plate_door: None,
+    };
+    explorer.explore(&start, None)?;
+
+    for door in explorer.route(&start.name, CHECKPOINT)? {
+        explorer.terminal.send(&door);
+    }
+    explorer.pass_checkpoint()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A stand in for the Intcode ship, with the same kind of text
+    struct MockShip {
+        rooms: HashMap<&'static str, MockRoom>,
+        at: &'static str,
+        weights: HashMap<String, u32>,
+        inventory: Vec<String>,
+        target: u32,
+        output: String,
+        commands: usize,
+    }
+
+    struct MockRoom {
+        doors: Vec<(&'static str, &'static str)>,
+        items: Vec<String>,
+    }
+
+    impl MockShip {
+        fn new() -> MockShip {
+            let mut rooms = HashMap::new();
+            let mut room = |name, doors, items: &[&str]| {
+                let items = items.iter().map(|i| i.to_string()).collect();
+                rooms.insert(name, MockRoom { doors, items });
+            };
+            room(
+                "Hull Breach",
+                vec![("north", "Kitchen"), ("east", "Corridor")],
+                &[],
+            );
+            room(
+                "Kitchen",
+                vec![("south", "Hull Breach"), ("west", "Arcade")],
+                &["mug", "molten lava"],
+            );
+            room("Arcade", vec![("east", "Kitchen")], &["coin", "photons"]);
+            room(
+                "Corridor",
+                vec![("west", "Hull Breach"), ("south", CHECKPOINT)],
+                &["spool of cat6", "shell"],
+            );
+            room(
+                CHECKPOINT,
+                vec![("north", "Corridor"), ("east", "Pressure-Sensitive Floor")],
+                &["jam"],
+            );
+
+            let weights = [
+                ("mug", 1),
+                ("coin", 2),
+                ("spool of cat6", 4),
+                ("shell", 8),
+                ("jam", 16),
+            ]
+            .iter()
+            .map(|(i, w)| (i.to_string(), *w))
+            .collect();
+
+            let mut ship = MockShip {
+                rooms,
+                at: "Hull Breach",
+                weights,
+                inventory: Vec::new(),
+                target: 1 + 4 + 16,
+                output: String::new(),
+                commands: 0,
+            };
+            ship.output = ship.describe();
+            ship
+        }
+
+        fn describe(&self) -> String {
+            let room = &self.rooms[self.at];
+            let mut text = format!(
+                "\n\n\n== {} ==\nA room on the ship.\n\nDoors here lead:\n",
+                self.at
+            );
+            for (door, _) in room.doors.iter() {
+                text += &format!("- {}\n", door);
+            }
+            if !room.items.is_empty() {
+                text += "\nItems here:\n";
+                for item in room.items.iter() {
+                    text += &format!("- {}\n", item);
+                }
+            }
+            text + "\nCommand?\n"
+        }
+
+        fn respond(&mut self, command: &str) -> String {
+            if let Some(item) = command.strip_prefix("take ") {
+                assert!(!DEADLY_ITEMS.contains(&item), "Took the {}", item);
+                let items = &mut self.rooms.get_mut(self.at).unwrap().items;
+                let idx = items
+                    .iter()
+                    .position(|i| i == item)
+                    .expect("The item is here");
+                self.inventory.push(items.remove(idx));
+                return format!("\nYou take the {}.\n\nCommand?\n", item);
+            }
+            if let Some(item) = command.strip_prefix("drop ") {
+                let idx = self
+                    .inventory
+                    .iter()
+                    .position(|i| i == item)
+                    .expect("Holding it");
+                let item = self.inventory.remove(idx);
+                self.rooms
+                    .get_mut(self.at)
+                    .unwrap()
+                    .items
+                    .push(item.clone());
+                return format!("\nYou drop the {}.\n\nCommand?\n", item);
+            }
+
+            let room = &self.rooms[self.at];
+            let (_, to) = *room
+                .doors
+                .iter()
+                .find(|(d, _)| *d == command)
+                .expect("There's a door that way");
+            if to != "Pressure-Sensitive Floor" {
+                self.at = to;
+                return self.describe();
+            }
+
+            let weight: u32 = self.inventory.iter().map(|i| self.weights[i]).sum();
+            let verdict = match weight.cmp(&self.target) {
+                std::cmp::Ordering::Equal => {
+                    return "\n\n== Pressure-Sensitive Floor ==\nAnalysis complete! You may proceed.\n\"Oh, hello! You should be able to get in by typing 12345 on the keypad at the main airlock.\"\n".to_string();
+                }
+                std::cmp::Ordering::Less => "heavier",
+                std::cmp::Ordering::Greater => "lighter",
+            };
+            format!(
+                "\n\n== Pressure-Sensitive Floor ==\nA loud, robotic voice says \"Alert! Droids on this ship are {} than the detected value!\" and you are ejected back to the checkpoint.\n{}",
+                verdict,
+                self.describe()
+            )
+        }
+    }
+
+    impl Terminal for MockShip {
+        fn read(&mut self) -> String {
+            std::mem::take(&mut self.output)
+        }
+
+        fn write(&mut self, command: &str) {
+            self.commands += 1;
+            let response = self.respond(command);
+            self.output += &response;
+        }
+    }
+
+    #[test]
+    fn refuses_commands_the_droid_cannot_read() {
+        let program = crate::intcode::assemble("in [c]\nout [c]\nhlt\nc: data 0").unwrap();
+        let mut adventure = Adventure::new(&program);
+        assert_eq!(Err(NotAscii), adventure.try_write("nörth"));
+        assert_eq!(Ok(()), adventure.try_write("n"));
+        assert_eq!("n", adventure.read());
+    }
+
+    #[test]
+    fn parses_rooms() {
+        let ship = MockShip::new();
+        assert_eq!(
+            Some(Room {
+                name: "Hull Breach".to_string(),
+                doors: vec!["north".to_string(), "east".to_string()],
+                items: vec![],
+            }),
+            parse_room(&ship.describe())
+        );
+
+        let mut ship = MockShip::new();
+        ship.send("east");
+        let ejected = ship.send("south") + &ship.send("east");
+        let room = parse_room(&ejected).unwrap();
+        assert_eq!(CHECKPOINT, room.name);
+        assert_eq!(vec!["jam".to_string()], room.items);
+        assert_eq!(None, password(&ejected));
+    }
+
+    #[test]
+    fn explores_and_passes_the_checkpoint() {
+        let mut ship = MockShip::new();
+        assert_eq!(Some("12345".to_string()), solve(&mut ship));
+
+        let mut held = ship.inventory.clone();
+        held.sort();
+        assert_eq!(vec!["jam", "mug", "spool of cat6"], held);
+        assert!(ship.commands < 100, "Took {} commands", ship.commands);
+    }
+}
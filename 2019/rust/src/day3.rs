@@ -1,3 +1,4 @@
+use crate::solution::{self, Skipped, Solution};
 use crate::util::{self, CoordinateMapping, Direction, Orientation};
 use regex::Regex;
 use std::collections::HashMap;
@@ -6,7 +7,7 @@ use std::convert::TryInto;
 type Grid = i64;
 type Point = crate::util::Point<Grid>;
 
-struct Intersection {
+pub struct Intersection {
     point: Point,
     total_length: Grid,
 }
@@ -225,18 +226,31 @@ fn input() -> WireSet {
     WireSet::from(util::read_file("inputs/day3.txt").lines()).unwrap()
 }
 
-fn calculate_day_three() -> (Grid, Grid) {
-    let set = input();
-    let intersections = set.all_intersections();
-    let manhattan = lowest_manhattan_excluding_origin(&intersections).unwrap();
-    let steps = lowest_steps_excluding_origin(&intersections).unwrap();
-    (manhattan, steps)
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2019;
+    const DAY: u32 = 3;
+
+    type Input = Intersections;
+    type PartOne = Grid;
+    type PartTwo = Grid;
+
+    fn parse() -> Result<Intersections, Skipped> {
+        Ok(input().all_intersections())
+    }
+
+    fn part_one(input: &Intersections) -> Grid {
+        lowest_manhattan_excluding_origin(input).unwrap()
+    }
+
+    fn part_two(input: &Intersections) -> Grid {
+        lowest_steps_excluding_origin(input).unwrap()
+    }
 }
 
 pub fn run_day_three() {
-    let (manhattan, steps) = calculate_day_three();
-    println!("Day 3, Part 1: {}", manhattan);
-    println!("Day 3, Part 2: {}", steps);
+    solution::run::<Solver>();
 }
 
 #[test]
@@ -341,7 +355,7 @@ fn example_distance_3() {
 
 #[test]
 fn actual_day_3() {
-    let (manhattan, steps) = calculate_day_three();
+    let (manhattan, steps) = solution::answers::<Solver>();
     assert_eq!(352, manhattan);
     assert_eq!(43848, steps);
 }
@@ -1,3 +1,4 @@
+use crate::solution::{self, Skipped, Solution};
 use std::fmt::Write;
 
 fn is_valid(digits: &str, enforce_doubles_only: bool) -> bool {
@@ -48,19 +49,32 @@ fn valids_between(start: i32, end: i32, enforce_doubles_only: bool) -> usize {
         .count()
 }
 
-fn calculate_day_four() -> (usize, usize) {
-    let start = 146810;
-    let end = 612564;
-    (
-        valids_between(start, end, false),
-        valids_between(start, end, true),
-    )
+// The puzzle input is just the range to search
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2019;
+    const DAY: u32 = 4;
+
+    type Input = (i32, i32);
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse() -> Result<(i32, i32), Skipped> {
+        Ok((146810, 612564))
+    }
+
+    fn part_one(input: &(i32, i32)) -> usize {
+        valids_between(input.0, input.1, false)
+    }
+
+    fn part_two(input: &(i32, i32)) -> usize {
+        valids_between(input.0, input.1, true)
+    }
 }
 
 pub fn run_day_four() {
-    let (part_one, part_two) = calculate_day_four();
-    println!("Day 4, Part 1: {}", part_one);
-    println!("Day 4, Part 2: {}", part_two);
+    solution::run::<Solver>();
 }
 
 #[test]
@@ -79,7 +93,7 @@ fn example_2() {
 
 #[test]
 fn actual_day_3() {
-    let (part_one, part_two) = calculate_day_four();
+    let (part_one, part_two) = solution::answers::<Solver>();
     assert_eq!(1748, part_one);
     assert_eq!(1180, part_two);
 }
@@ -1,26 +1,59 @@
 use crate::intcode::*;
+use crate::solution::{self, Skipped, Solution};
 use crate::util;
 
 fn input() -> Vec<MemoryCell> {
     util::read_int_array("inputs/day5.txt")
 }
 
-fn calculate_day_five() -> (MemoryCell, MemoryCell) {
-    let input = input();
-    let part_one = run_io_intcode_program(input.clone(), &[1]).unwrap();
-    let part_two = run_io_intcode_program(input.clone(), &[5]).unwrap();
-    (part_one, part_two)
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2019;
+    const DAY: u32 = 5;
+
+    type Input = Vec<MemoryCell>;
+    type PartOne = MemoryCell;
+    type PartTwo = MemoryCell;
+
+    fn parse() -> Result<Vec<MemoryCell>, Skipped> {
+        Ok(input())
+    }
+
+    fn part_one(input: &Vec<MemoryCell>) -> MemoryCell {
+        run_io_intcode_program(input.clone(), &[1]).unwrap()
+    }
+
+    fn part_two(input: &Vec<MemoryCell>) -> MemoryCell {
+        run_io_intcode_program(input.clone(), &[5]).unwrap()
+    }
 }
 
 pub fn run_day_five() {
-    let (part_one, part_two) = calculate_day_five();
-    println!("Day 5, Part 1: {}", part_one);
-    println!("Day 5, Part 2: {}", part_two);
+    solution::run::<Solver>();
 }
 
 #[test]
 fn actual_day_5() {
-    let (part_one, part_two) = calculate_day_five();
+    let (part_one, part_two) = solution::answers::<Solver>();
     assert_eq!(12440243, part_one);
     assert_eq!(15486302, part_two);
 }
+
+#[test]
+fn example_equal_to_8() {
+    let program = assemble(
+        "
+                in   [x]
+                eq   [x], [eight], [x]
+                out  [x]
+                hlt
+        x:      data -1
+        eight:  data 8
+        ",
+    )
+    .unwrap();
+    assert_eq!(vec![3, 9, 8, 9, 10, 9, 4, 9, 99, -1, 8], program);
+    assert_eq!(1, run_io_intcode_program(program.clone(), &[8]).unwrap());
+    assert_eq!(0, run_io_intcode_program(program, &[7]).unwrap());
+}
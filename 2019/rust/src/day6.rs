@@ -1,7 +1,8 @@
+use crate::solution::{self, Skipped, Solution};
 use crate::util;
 use std::collections::{HashMap, VecDeque};
 
-struct Body {
+pub struct Body {
     label: String,
     parent: String,
 }
@@ -118,22 +119,38 @@ fn input() -> Bodies {
     parse_bodies(util::read_file("inputs/day6.txt").lines())
 }
 
-fn calculate_day_six() -> (usize, usize) {
-    let bodies = input();
-    let mut state = OrbitState::new(&bodies);
-    state.map();
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2019;
+    const DAY: u32 = 6;
 
-    let you = &state.lookup["YOU"];
-    let san = &state.lookup["SAN"];
-    let path = state.find_path(*you, *san) - 2;
+    type Input = Bodies;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse() -> Result<Bodies, Skipped> {
+        Ok(input())
+    }
 
-    (state.total, path)
+    fn part_one(input: &Bodies) -> usize {
+        let mut state = OrbitState::new(input);
+        state.map();
+        state.total
+    }
+
+    fn part_two(input: &Bodies) -> usize {
+        let mut state = OrbitState::new(input);
+        state.map();
+
+        let you = &state.lookup["YOU"];
+        let san = &state.lookup["SAN"];
+        state.find_path(*you, *san) - 2
+    }
 }
 
 pub fn run_day_six() {
-    let (part_one, part_two) = calculate_day_six();
-    println!("Day 6, Part 1: {}", part_one);
-    println!("Day 6, Part 2: {}", part_two);
+    solution::run::<Solver>();
 }
 
 #[test]
@@ -151,7 +168,7 @@ fn example_1() {
 
 #[test]
 fn actual_day_six() {
-    let (part_one, part_two) = calculate_day_six();
+    let (part_one, part_two) = solution::answers::<Solver>();
     assert_eq!(227612, part_one);
     assert_eq!(454, part_two);
 }
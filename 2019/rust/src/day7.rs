@@ -1,4 +1,5 @@
 use crate::intcode::*;
+use crate::solution::{self, Skipped, Solution};
 use crate::util;
 
 fn part_one_try_phases(state: &VecStorage, phases: &[MemoryCell]) -> MemoryCell {
@@ -18,49 +19,17 @@ fn part_one_find_max(state: &[MemoryCell]) -> MemoryCell {
 }
 
 fn part_two_try_phases(state: &[MemoryCell], phases: &[MemoryCell]) -> MemoryCell {
-    let make_a_computer = |&phase| {
-        let mut c = Computer::new(Vec::from(state), BufferInput::new(2), BufferOutput::new(1));
-        c.input().queue(phase);
-        c
-    };
-
-    let mut computers: Vec<Computer<VecStorage, BufferInput, BufferOutput>> =
-        phases.iter().map(make_a_computer).collect();
-
-    computers[0].input().queue(0);
-
-    let mut i = 0;
-    let mut result: Option<MemoryCell> = None;
-
-    loop {
-        let j = if i < computers.len() - 1 { i + 1 } else { 0 };
-        let outputs: Vec<MemoryCell>;
-
-        {
-            let this_computer = computers.get_mut(i).unwrap();
-            this_computer.resume().unwrap();
-
-            let output = this_computer.output();
-
-            if j == 0 {
-                result = output.last().or(result);
-            }
-
-            outputs = output.pop_all();
-        }
-
-        {
-            let next_computer = computers.get_mut(j).unwrap();
-
-            if next_computer.has_halted() {
-                return result.expect("Got to the end of the chain, but there was no result");
-            }
-
-            next_computer.input().queue_many(&outputs);
-        }
-
-        i = j;
+    let mut network = Network::from_topology("A -> B -> C -> D -> E -> A", state).unwrap();
+    for (name, phase) in ["A", "B", "C", "D", "E"].iter().zip(phases) {
+        network.queue(name, &[*phase]).unwrap();
     }
+    network.queue("A", &[0]).unwrap();
+
+    let result = network.run().unwrap();
+    *result
+        .outputs("E")
+        .and_then(|o| o.last())
+        .expect("Got to the end of the chain, but there was no result")
 }
 
 fn part_two_find_max(state: &[MemoryCell]) -> MemoryCell {
@@ -76,17 +45,31 @@ fn input() -> Vec<MemoryCell> {
     util::read_int_array("inputs/day7.txt")
 }
 
-fn calculate_day_seven() -> (MemoryCell, MemoryCell) {
-    let input = input();
-    let part_one = part_one_find_max(&input);
-    let part_two = part_two_find_max(&input);
-    (part_one, part_two)
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2019;
+    const DAY: u32 = 7;
+
+    type Input = Vec<MemoryCell>;
+    type PartOne = MemoryCell;
+    type PartTwo = MemoryCell;
+
+    fn parse() -> Result<Vec<MemoryCell>, Skipped> {
+        Ok(input())
+    }
+
+    fn part_one(input: &Vec<MemoryCell>) -> MemoryCell {
+        part_one_find_max(input)
+    }
+
+    fn part_two(input: &Vec<MemoryCell>) -> MemoryCell {
+        part_two_find_max(input)
+    }
 }
 
 pub fn run_day_seven() {
-    let (part_one, part_two) = calculate_day_seven();
-    println!("Day 7, Part 1: {}", part_one);
-    println!("Day 7, Part 2: {}", part_two);
+    solution::run::<Solver>();
 }
 
 fn get_permutations(values: &[MemoryCell]) -> Vec<[MemoryCell; 5]> {
@@ -178,7 +161,7 @@ fn example_5() {
 
 #[test]
 fn actual_day_7() {
-    let (part_one, part_two) = calculate_day_seven();
+    let (part_one, part_two) = solution::answers::<Solver>();
     assert_eq!(199988, part_one);
     assert_eq!(17519904, part_two);
 }
@@ -1,3 +1,4 @@
+use crate::solution::{self, Skipped, Solution};
 use crate::util;
 
 type ImageDimension = usize;
@@ -103,18 +104,19 @@ impl<'a> Iterator for LayerIter<'a> {
     }
 }
 
-fn render(image: &SpaceImage) {
+fn render(image: &SpaceImage) -> String {
+    let mut picture = String::new();
     for i in 0..image.height {
         for j in 0..image.width {
             for l in 0..image.layer_count() {
                 let layer = image.get_layer(l).unwrap();
                 match layer.get_pixel(j, i) {
                     PixelType::Black => {
-                        print!(" ");
+                        picture.push(' ');
                         break;
                     }
                     PixelType::White => {
-                        print!("█");
+                        picture.push('█');
                         break;
                     }
                     PixelType::Transparent => {
@@ -123,8 +125,9 @@ fn render(image: &SpaceImage) {
                 }
             }
         }
-        println!("");
+        picture.push('\n');
     }
+    picture
 }
 
 fn input() -> String {
@@ -138,12 +141,31 @@ fn calculate_day_eight(input: &str) -> (usize, SpaceImage) {
     (calc, image)
 }
 
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2019;
+    const DAY: u32 = 8;
+
+    type Input = String;
+    type PartOne = usize;
+    type PartTwo = String;
+
+    fn parse() -> Result<String, Skipped> {
+        Ok(input())
+    }
+
+    fn part_one(input: &String) -> usize {
+        calculate_day_eight(input).0
+    }
+
+    fn part_two(input: &String) -> String {
+        render(&SpaceImage::new(input, 25, 6))
+    }
+}
+
 pub fn run_day_eight() {
-    let input = input();
-    let (part_1, image) = calculate_day_eight(&input);
-    println!("Day 8, Part 1: {}", part_1);
-    println!("Day 8, Part 2:");
-    render(&image);
+    solution::run::<Solver>();
 }
 
 #[test]
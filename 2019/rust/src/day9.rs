@@ -1,4 +1,5 @@
 use crate::intcode::*;
+use crate::solution::{self, Skipped, Solution};
 use crate::util;
 
 fn input() -> Vec<MemoryCell> {
@@ -6,36 +7,57 @@ fn input() -> Vec<MemoryCell> {
 }
 
 fn run_with_input(code: Vec<MemoryCell>, input: MemoryCell) -> Vec<MemoryCell> {
-    let mut computer = Computer::new(code, BufferInput::new(1), BufferOutput::new(1));
-    computer.enable_extra_memory();
+    let mut computer = Computer::new(
+        PagedStorage::from(code),
+        BufferInput::new(1),
+        BufferOutput::new(1),
+    );
     computer.input().queue(input);
     computer.run_until_halt().unwrap();
     let output = computer.output();
     output.pop_all()
 }
 
-fn calculate_day_nine() -> (Vec<MemoryCell>, Option<Vec<MemoryCell>>) {
-    let code = input();
-    let part_1 = run_with_input(code.clone(), 1);
-    let part_2: Option<Vec<MemoryCell>> = None;
-    #[cfg(slow_problems)]
-    let part_2 = Some(run_with_input(code.clone(), 2));
-    (part_1, part_2)
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2019;
+    const DAY: u32 = 9;
+
+    type Input = Vec<MemoryCell>;
+    type PartOne = String;
+    type PartTwo = String;
+
+    fn parse() -> Result<Vec<MemoryCell>, Skipped> {
+        Ok(input())
+    }
+
+    fn part_one(input: &Vec<MemoryCell>) -> String {
+        format!("{:?}", run_with_input(input.clone(), 1))
+    }
+
+    fn part_two(_input: &Vec<MemoryCell>) -> String {
+        let part_2: Option<Vec<MemoryCell>> = None;
+        #[cfg(slow_problems)]
+        let part_2 = Some(run_with_input(_input.clone(), 2));
+        format!("{:?}", part_2)
+    }
 }
 
 pub fn run_day_nine() {
-    let (part_one, part_two) = calculate_day_nine();
-    println!("Day 9, Part 1: {:?}", part_one);
-    println!("Day 9, Part 2: {:?}", part_two);
+    solution::run::<Solver>();
 }
 
 #[test]
 fn example_1() {
-    let state = &mut [
+    let state: &mut [MemoryCell] = &mut [
         109, 1, 204, -1, 1001, 100, 1, 100, 1008, 100, 16, 101, 1006, 101, 0, 99,
     ];
-    let mut computer = Computer::new(slice_storage(state), NoInput, BufferOutput::new(16));
-    computer.enable_extra_memory();
+    let mut computer = Computer::new(
+        PagedStorage::from(&state[..]),
+        NoInput,
+        BufferOutput::new(16),
+    );
     computer.run_until_halt().unwrap();
     let output = computer.output();
     assert_eq!(
@@ -64,8 +86,7 @@ fn example_3() {
 
 #[test]
 fn actual_day_9() {
-    let (part_one, _part_two) = calculate_day_nine();
-    assert_eq!(vec!(3601950151), part_one);
+    assert_eq!(vec!(3601950151), run_with_input(input(), 1));
     #[cfg(slow_problems)]
-    assert_eq!(vec!(64236), _part_two.unwrap());
+    assert_eq!(vec!(64236), run_with_input(input(), 2));
 }
@@ -0,0 +1,525 @@
+use super::computer::{OpCode, Operation, Parameter};
+use super::decode::decode;
+use super::{MemoryCell, MemoryPointer};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+// How control leaves the end of a basic block
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Terminator {
+    Halt,
+    // The next instruction couldn't be decoded
+    Invalid(MemoryPointer),
+    // Execution runs past the end of the program
+    FallOffEnd,
+    FallThrough(MemoryPointer),
+    Jump(MemoryPointer),
+    Branch {
+        taken: MemoryPointer,
+        not_taken: MemoryPointer,
+    },
+    // A jump made just after pushing a constant return address on to the stack
+    Call {
+        target: MemoryPointer,
+        returns_to: MemoryPointer,
+    },
+    // A jump to an address only known at runtime, such as a return address on the stack
+    Indirect {
+        not_taken: Option<MemoryPointer>,
+    },
+}
+
+impl Terminator {
+    pub fn successors(&self) -> Vec<MemoryPointer> {
+        match *self {
+            Terminator::FallThrough(to) | Terminator::Jump(to) => vec![to],
+            Terminator::Branch { taken, not_taken } => vec![taken, not_taken],
+            Terminator::Call { target, returns_to } => vec![target, returns_to],
+            Terminator::Indirect { not_taken } => not_taken.into_iter().collect(),
+            Terminator::Halt | Terminator::Invalid(_) | Terminator::FallOffEnd => vec![],
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct BasicBlock {
+    start: MemoryPointer,
+    instructions: Vec<(MemoryPointer, Operation)>,
+    terminator: Terminator,
+}
+
+impl BasicBlock {
+    pub fn start(&self) -> MemoryPointer {
+        self.start
+    }
+
+    pub fn instructions(&self) -> &[(MemoryPointer, Operation)] {
+        &self.instructions
+    }
+
+    pub fn terminator(&self) -> Terminator {
+        self.terminator
+    }
+}
+
+// An instruction which writes to an address holding code
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct SelfModification {
+    pub address: MemoryPointer,
+    pub target: MemoryPointer,
+}
+
+pub struct ControlFlowGraph {
+    blocks: BTreeMap<MemoryPointer, BasicBlock>,
+    self_modifications: Vec<SelfModification>,
+}
+
+// Which ways a jump instruction might go: (might jump, target if known, might fall through)
+fn jump_outcomes(operation: &Operation) -> (bool, Option<MemoryPointer>, bool) {
+    let (condition, target) = match operation.inputs() {
+        [condition, target] => (condition, target),
+        _ => unreachable!("Jumps have two inputs"),
+    };
+
+    let jumps_on_zero = operation.op_code() == OpCode::JumpIfFalse;
+    let (may_jump, may_fall) = match condition {
+        Parameter::Immediate(value) => {
+            let jumps = (*value == 0) == jumps_on_zero;
+            (jumps, !jumps)
+        }
+        _ => (true, true),
+    };
+
+    let target = match target {
+        Parameter::Immediate(value) if *value >= 0 => Some(*value as MemoryPointer),
+        _ => None,
+    };
+
+    (may_jump, target, may_fall)
+}
+
+fn is_jump(operation: &Operation) -> bool {
+    matches!(
+        operation.op_code(),
+        OpCode::JumpIfTrue | OpCode::JumpIfFalse
+    )
+}
+
+fn next_address(at: MemoryPointer, operation: &Operation) -> MemoryPointer {
+    at + 1 + operation.inputs().len() + operation.output().map_or(0, |_| 1)
+}
+
+// Programs call functions by storing the return address at the top of the stack,
+// then jumping unconditionally. Find the jump and the return address, if this is one.
+fn as_call(
+    program: &[MemoryCell],
+    at: MemoryPointer,
+    operation: &Operation,
+) -> Option<(MemoryPointer, MemoryPointer)> {
+    if operation.output() != Some(&Parameter::Relative(0)) {
+        return None;
+    }
+    let value = match (operation.op_code(), operation.inputs()) {
+        (OpCode::Add, [Parameter::Immediate(a), Parameter::Immediate(b)]) => a.checked_add(*b)?,
+        (OpCode::Multiply, [Parameter::Immediate(a), Parameter::Immediate(b)]) => {
+            a.checked_mul(*b)?
+        }
+        _ => return None,
+    };
+    if value < 0 {
+        return None;
+    }
+
+    // Skip over the stack pointer being moved past the return address
+    let mut jump_at = next_address(at, operation);
+    let mut jump = decode(program, jump_at).ok()?;
+    if jump.op_code() == OpCode::SetRelativeBase {
+        jump_at = next_address(jump_at, &jump);
+        jump = decode(program, jump_at).ok()?;
+    }
+    if !is_jump(&jump) {
+        return None;
+    }
+    match jump_outcomes(&jump) {
+        (true, Some(_), false) => Some((jump_at, value as MemoryPointer)),
+        _ => None,
+    }
+}
+
+// Find every instruction reachable from address 0, decoding them exactly as the Computer would.
+// Jumps to immediate addresses are followed, as are the return addresses of calls.
+// Anything else is left as an indirect jump.
+pub fn analyse(program: &[MemoryCell]) -> ControlFlowGraph {
+    let mut decoded: BTreeMap<MemoryPointer, Option<Operation>> = BTreeMap::new();
+    let mut leaders: BTreeSet<MemoryPointer> = BTreeSet::new();
+    let mut calls: BTreeMap<MemoryPointer, MemoryPointer> = BTreeMap::new();
+    let mut pending = vec![0];
+    leaders.insert(0);
+
+    while let Some(at) = pending.pop() {
+        if decoded.contains_key(&at) || at >= program.len() {
+            continue;
+        }
+
+        let operation = decode(program, at).ok();
+        decoded.insert(at, operation);
+        let operation = match operation {
+            Some(op) => op,
+            None => continue,
+        };
+
+        let next = next_address(at, &operation);
+        if operation.op_code() == OpCode::Halt {
+            continue;
+        } else if is_jump(&operation) {
+            let (may_jump, target, may_fall) = jump_outcomes(&operation);
+            if let (true, Some(target)) = (may_jump, target) {
+                leaders.insert(target);
+                pending.push(target);
+            }
+            if may_fall {
+                leaders.insert(next);
+                pending.push(next);
+            }
+        } else {
+            if let Some((jump_at, returns_to)) = as_call(program, at, &operation) {
+                calls.insert(jump_at, returns_to);
+                leaders.insert(returns_to);
+                pending.push(returns_to);
+            }
+            pending.push(next);
+        }
+    }
+
+    let mut blocks = BTreeMap::new();
+    for &start in leaders.iter().filter(|at| decoded.contains_key(at)) {
+        let mut instructions = Vec::new();
+        let mut at = start;
+        let terminator = loop {
+            let operation = match decoded.get(&at) {
+                Some(Some(op)) => *op,
+                Some(None) => break Terminator::Invalid(at),
+                None => break Terminator::FallOffEnd,
+            };
+            instructions.push((at, operation));
+
+            let next = next_address(at, &operation);
+            if operation.op_code() == OpCode::Halt {
+                break Terminator::Halt;
+            }
+            if is_jump(&operation) {
+                break match jump_outcomes(&operation) {
+                    (true, None, may_fall) => Terminator::Indirect {
+                        not_taken: Some(next).filter(|_| may_fall),
+                    },
+                    (true, Some(taken), true) => Terminator::Branch {
+                        taken,
+                        not_taken: next,
+                    },
+                    (true, Some(target), false) => match calls.get(&at) {
+                        Some(&returns_to) => Terminator::Call { target, returns_to },
+                        None => Terminator::Jump(target),
+                    },
+                    (false, _, _) => Terminator::FallThrough(next),
+                };
+            }
+            if leaders.contains(&next) {
+                break Terminator::FallThrough(next);
+            }
+            at = next;
+        };
+
+        blocks.insert(
+            start,
+            BasicBlock {
+                start,
+                instructions,
+                terminator,
+            },
+        );
+    }
+
+    // Any write to a cell that we've decoded as part of an instruction changes the code
+    let code_cells: BTreeSet<MemoryPointer> = decoded
+        .iter()
+        .filter_map(|(&at, op)| op.map(|op| at..next_address(at, &op)))
+        .flatten()
+        .collect();
+    let self_modifications = decoded
+        .iter()
+        .filter_map(|(&at, op)| match op.as_ref()?.output()? {
+            Parameter::Position(target) if code_cells.contains(target) => Some(SelfModification {
+                address: at,
+                target: *target,
+            }),
+            _ => None,
+        })
+        .collect();
+
+    ControlFlowGraph {
+        blocks,
+        self_modifications,
+    }
+}
+
+impl ControlFlowGraph {
+    pub fn blocks(&self) -> impl Iterator<Item = &BasicBlock> {
+        self.blocks.values()
+    }
+
+    pub fn block_at(&self, start: MemoryPointer) -> Option<&BasicBlock> {
+        self.blocks.get(&start)
+    }
+
+    pub fn self_modifications(&self) -> &[SelfModification] {
+        &self.self_modifications
+    }
+
+    fn modifies_code(&self, at: MemoryPointer) -> bool {
+        self.self_modifications.iter().any(|m| m.address == at)
+    }
+
+    // Render the graph in Graphviz DOT format. Blocks which modify code are shaded, and
+    // jumps out of the program go to a dashed placeholder.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        writeln!(dot, "digraph intcode {{").unwrap();
+        writeln!(dot, "  node [shape=box, fontname=\"monospace\"];").unwrap();
+
+        for block in self.blocks.values() {
+            let mut label = String::new();
+            for (at, operation) in &block.instructions {
+                let operands: Vec<String> = operation
+                    .inputs()
+                    .iter()
+                    .chain(operation.output())
+                    .map(|p| p.to_string())
+                    .collect();
+                write!(
+                    label,
+                    "{}: {} {}\\l",
+                    at,
+                    operation.op_code().mnemonic(),
+                    operands.join(", ")
+                )
+                .unwrap();
+            }
+            match block.terminator {
+                Terminator::Invalid(at) => write!(label, "{}: ???\\l", at).unwrap(),
+                Terminator::FallOffEnd => label.push_str("end of program\\l"),
+                _ => (),
+            }
+
+            let modifies = block
+                .instructions
+                .iter()
+                .any(|(at, _)| self.modifies_code(*at));
+            let style = match modifies {
+                true => ", style=filled, fillcolor=\"#ffcccc\"",
+                false => "",
+            };
+            writeln!(dot, "  b{} [label=\"{}\"{}];", block.start, label, style).unwrap();
+
+            match block.terminator {
+                Terminator::Branch { taken, not_taken } => {
+                    writeln!(dot, "  b{} -> b{} [label=\"taken\"];", block.start, taken).unwrap();
+                    writeln!(dot, "  b{} -> b{};", block.start, not_taken).unwrap();
+                }
+                Terminator::Call { target, returns_to } => {
+                    writeln!(dot, "  b{} -> b{} [label=\"call\"];", block.start, target).unwrap();
+                    writeln!(
+                        dot,
+                        "  b{} -> b{} [style=dotted, label=\"return\"];",
+                        block.start, returns_to
+                    )
+                    .unwrap();
+                }
+                Terminator::Indirect { not_taken } => {
+                    writeln!(
+                        dot,
+                        "  b{0}_indirect [label=\"?\", shape=circle];\n  b{0} -> b{0}_indirect [style=dashed];",
+                        block.start
+                    )
+                    .unwrap();
+                    if let Some(not_taken) = not_taken {
+                        writeln!(dot, "  b{} -> b{};", block.start, not_taken).unwrap();
+                    }
+                }
+                other => {
+                    for to in other.successors() {
+                        writeln!(dot, "  b{} -> b{};", block.start, to).unwrap();
+                    }
+                }
+            }
+        }
+
+        let outside: BTreeSet<MemoryPointer> = self
+            .blocks
+            .values()
+            .flat_map(|block| block.terminator.successors())
+            .filter(|to| !self.blocks.contains_key(to))
+            .collect();
+        for to in outside {
+            writeln!(
+                dot,
+                "  b{0} [label=\"{0}: outside the program\", style=dashed];",
+                to
+            )
+            .unwrap();
+        }
+
+        writeln!(dot, "}}").unwrap();
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+
+    #[test]
+    fn splits_blocks_at_jumps_and_targets() {
+        let program = assemble(
+            "
+                    in   [n]
+            loop:   out  [n]
+                    add  [n], #-1, [n]
+                    jt   [n], #loop
+                    jf   #0, #end
+                    out  #-1
+            end:    hlt
+            n:      data 0
+            ",
+        )
+        .unwrap();
+        let graph = analyse(&program);
+        let starts: Vec<MemoryPointer> = graph.blocks().map(|b| b.start()).collect();
+        assert_eq!(vec![0, 2, 11, 16], starts);
+
+        assert_eq!(
+            Terminator::FallThrough(2),
+            graph.block_at(0).unwrap().terminator()
+        );
+        assert_eq!(
+            Terminator::Branch {
+                taken: 2,
+                not_taken: 11
+            },
+            graph.block_at(2).unwrap().terminator()
+        );
+        // The jump is always taken, so the output after it is unreachable
+        assert_eq!(
+            Terminator::Jump(16),
+            graph.block_at(11).unwrap().terminator()
+        );
+        assert_eq!(Terminator::Halt, graph.block_at(16).unwrap().terminator());
+        assert!(graph.self_modifications().is_empty());
+    }
+
+    #[test]
+    fn finds_indirect_jumps_and_self_modification() {
+        let program = assemble(
+            "
+                    add  #104, #0, [patch]
+            patch:  out  #7
+                    jf   #0, [rb+0]
+            ",
+        )
+        .unwrap();
+        let graph = analyse(&program);
+
+        assert_eq!(
+            Terminator::Indirect { not_taken: None },
+            graph.block_at(0).unwrap().terminator()
+        );
+        assert_eq!(
+            &[SelfModification {
+                address: 0,
+                target: 4
+            }],
+            graph.self_modifications()
+        );
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph intcode {\n"));
+        assert!(dot.contains(
+            "b0 [label=\"0: add #104, #0, [4]\\l4: out #7\\l6: jf #0, [rb+0]\\l\", style=filled"
+        ));
+        assert!(dot.contains("b0 -> b0_indirect [style=dashed];"));
+    }
+
+    #[test]
+    fn declares_jump_targets_outside_the_program() {
+        let program = assemble("jf [n], #100\nhlt\nn: data 0").unwrap();
+        let graph = analyse(&program);
+        assert_eq!(
+            Terminator::Branch {
+                taken: 100,
+                not_taken: 3
+            },
+            graph.block_at(0).unwrap().terminator()
+        );
+        assert!(graph.block_at(100).is_none());
+
+        let dot = graph.to_dot();
+        assert!(dot.contains("b0 -> b100 [label=\"taken\"];"));
+        assert!(dot.contains("b100 [label=\"100: outside the program\", style=dashed];"));
+        assert!(!dot.contains("b3 [label=\"3: outside"));
+    }
+
+    #[test]
+    fn follows_calls_to_their_return_address() {
+        let program = assemble(
+            "
+                    push #back
+                    jt   #1, #double
+            back:   hlt
+            double: pop  [x]
+                    out  [x]
+                    jf   #0, [x]
+            x:      data 0
+            ",
+        )
+        .unwrap();
+        let graph = analyse(&program);
+
+        assert_eq!(
+            Terminator::Call {
+                target: 10,
+                returns_to: 9
+            },
+            graph.block_at(0).unwrap().terminator()
+        );
+        assert_eq!(Terminator::Halt, graph.block_at(9).unwrap().terminator());
+        assert_eq!(
+            Terminator::Indirect { not_taken: None },
+            graph.block_at(10).unwrap().terminator()
+        );
+    }
+
+    #[test]
+    fn ignores_immediates_which_overflow() {
+        let max = MemoryCell::MAX;
+        for program in [
+            vec![1101, max, 1, 0, 99],
+            vec![1102, max, 2, 0, 99],
+            vec![21101, max, 1, 0, 1105, 1, 9, 99, 99, 99],
+            vec![21102, max, max, 0, 1105, 1, 9, 99, 99, 99],
+        ] {
+            let graph = analyse(&program);
+            assert_eq!(Some(0), graph.block_at(0).map(|b| b.start()));
+        }
+    }
+
+    #[test]
+    fn analyses_puzzle_inputs() {
+        for day in [9, 13, 15, 17] {
+            let program = load_program(&format!("inputs/day{}.txt", day));
+            let graph = analyse(&program);
+            assert!(graph.blocks().count() > 10);
+            assert!(graph
+                .blocks()
+                .all(|b| !matches!(b.terminator(), Terminator::Invalid(_))));
+        }
+    }
+}
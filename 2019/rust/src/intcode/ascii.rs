@@ -0,0 +1,179 @@
+use super::io::{InputSource, OutputSink};
+use super::MemoryCell;
+use std::collections::VecDeque;
+
+const NEWLINE: MemoryCell = 10;
+
+// Feeds text to a program one character at a time
+pub struct AsciiInput {
+    buf: VecDeque<MemoryCell>,
+}
+
+impl AsciiInput {
+    pub fn new() -> AsciiInput {
+        AsciiInput {
+            buf: VecDeque::new(),
+        }
+    }
+
+    // Queue a line of text, followed by a newline
+    pub fn queue_line(&mut self, line: &str) {
+        assert!(line.is_ascii(), "Can only send ASCII text: {}", line);
+        self.buf.extend(line.bytes().map(|b| b as MemoryCell));
+        self.buf.push_back(NEWLINE);
+    }
+
+    pub fn queue_lines(&mut self, lines: &[&str]) {
+        for line in lines {
+            self.queue_line(line);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+}
+
+impl Default for AsciiInput {
+    fn default() -> Self {
+        AsciiInput::new()
+    }
+}
+
+impl InputSource for AsciiInput {
+    fn next(&mut self) -> Option<MemoryCell> {
+        self.buf.pop_front()
+    }
+}
+
+// Collects text written by a program. Anything outside the ASCII range is
+// usually the answer to the puzzle, so it is kept apart from the text.
+pub struct AsciiOutput {
+    text: String,
+    current_line: String,
+    lines: VecDeque<String>,
+    values: Vec<MemoryCell>,
+}
+
+impl AsciiOutput {
+    pub fn new() -> AsciiOutput {
+        AsciiOutput {
+            text: String::new(),
+            current_line: String::new(),
+            lines: VecDeque::new(),
+            values: Vec::new(),
+        }
+    }
+
+    // All of the text written since the last call to take_text
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn take_text(&mut self) -> String {
+        std::mem::take(&mut self.text)
+    }
+
+    // The next line which has been completed by a newline
+    pub fn pop_line(&mut self) -> Option<String> {
+        self.lines.pop_front()
+    }
+
+    pub fn pop_lines(&mut self) -> Vec<String> {
+        self.lines.drain(..).collect()
+    }
+
+    // Text written since the last newline, such as a prompt
+    pub fn partial_line(&self) -> &str {
+        &self.current_line
+    }
+
+    pub fn non_ascii(&self) -> &[MemoryCell] {
+        &self.values
+    }
+
+    pub fn last_non_ascii(&self) -> Option<MemoryCell> {
+        self.values.last().copied()
+    }
+}
+
+impl Default for AsciiOutput {
+    fn default() -> Self {
+        AsciiOutput::new()
+    }
+}
+
+impl OutputSink for AsciiOutput {
+    fn write(&mut self, value: MemoryCell) {
+        if !(0..128).contains(&value) {
+            self.values.push(value);
+            return;
+        }
+
+        let symbol = value as u8 as char;
+        self.text.push(symbol);
+        if value == NEWLINE {
+            self.lines.push_back(std::mem::take(&mut self.current_line));
+        } else {
+            self.current_line.push(symbol);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+
+    #[test]
+    fn input_appends_newlines() {
+        let mut input = AsciiInput::new();
+        input.queue_lines(&["A,B", "L"]);
+        let mut values = Vec::new();
+        while let Some(v) = input.next() {
+            values.push(v);
+        }
+        assert_eq!(vec![65, 44, 66, 10, 76, 10], values);
+        assert!(input.is_empty());
+    }
+
+    #[test]
+    fn output_splits_lines_and_answers() {
+        let mut output = AsciiOutput::new();
+        for c in "Hello\nWorld\nInput?".bytes() {
+            output.write(c as MemoryCell);
+        }
+        output.write(123456);
+
+        assert_eq!(Some("Hello".to_string()), output.pop_line());
+        assert_eq!(vec!["World".to_string()], output.pop_lines());
+        assert_eq!(None, output.pop_line());
+        assert_eq!("Input?", output.partial_line());
+        assert_eq!("Hello\nWorld\nInput?", output.take_text());
+        assert_eq!("", output.text());
+        assert_eq!(Some(123456), output.last_non_ascii());
+    }
+
+    #[test]
+    fn echoes_through_a_program() {
+        let program = assemble(
+            "
+            loop:   in   [c]
+                    eq   [c], #10, [done]
+                    jt   [done], #end
+                    out  [c]
+                    jt   #1, #loop
+            end:    out  #-1
+                    hlt
+            c:      data 0
+            done:   data 0
+            ",
+        )
+        .unwrap();
+        let mut computer = Computer::new(program, AsciiInput::new(), AsciiOutput::new());
+        computer.input().queue_line("echo");
+        computer.run_until_halt().unwrap();
+
+        assert_eq!("echo", computer.output().text());
+        assert_eq!(&[-1], computer.output().non_ascii());
+    }
+}
@@ -0,0 +1,474 @@
+use super::computer::OpCode;
+use super::{MemoryCell, MemoryPointer};
+use std::collections::HashMap;
+
+// A small assembly language for Intcode, which reads the same syntax the disassembler writes:
+//
+//     ; Comments run to the end of the line
+//     start:  in   [value]                 ; [n] is position mode
+//             mul  [value], #2, [value]    ; #n is immediate mode
+//             out  [rb+1]                  ; [rb+n] is relative mode
+//             jt   #1, #start              ; Labels can be used anywhere a number can
+//        12:  hlt                          ; A numeric label asserts the current address
+//     value:  data 0, start, value+1
+//
+// There are also two relative base helpers, which treat the relative base as a stack pointer:
+// `push <operand>` stores a value at [rb+0] and advances the relative base, and `pop <operand>`
+// steps the relative base back and moves [rb+0] in to the operand.
+
+#[derive(Debug, PartialEq)]
+pub enum AssembleError {
+    UnknownMnemonic { line: usize },
+    WrongOperandCount { line: usize },
+    InvalidOperand { line: usize },
+    OutputOperandInImmediateMode { line: usize },
+    InvalidLabel { line: usize },
+    DuplicateLabel { line: usize },
+    UndefinedLabel { line: usize },
+    AddressMismatch { line: usize },
+}
+
+pub type AssembleResult<T> = Result<T, AssembleError>;
+
+#[derive(Debug, Clone)]
+enum Value {
+    Number(MemoryCell),
+    Label(String, MemoryCell),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mode {
+    Position,
+    Immediate,
+    Relative,
+}
+
+impl Mode {
+    fn digit(&self) -> MemoryCell {
+        match self {
+            Mode::Position => 0,
+            Mode::Immediate => 1,
+            Mode::Relative => 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Operand {
+    mode: Mode,
+    value: Value,
+}
+
+enum Item {
+    Label(String),
+    Address(MemoryPointer),
+    Instruction(OpCode, Vec<Operand>),
+    Data(Vec<Value>),
+}
+
+impl Item {
+    fn size(&self) -> MemoryPointer {
+        match self {
+            Item::Label(_) | Item::Address(_) => 0,
+            Item::Instruction(_, operands) => 1 + operands.len(),
+            Item::Data(values) => values.len(),
+        }
+    }
+}
+
+fn is_identifier(text: &str) -> bool {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {
+            text != "rb" && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }
+        _ => false,
+    }
+}
+
+fn parse_value(text: &str, line: usize) -> AssembleResult<Value> {
+    let text = text.trim();
+    if let Ok(n) = text.parse::<MemoryCell>() {
+        return Ok(Value::Number(n));
+    }
+
+    // A label, optionally followed by an offset
+    let (label, offset) = match text.find(['+', '-']) {
+        Some(idx) => {
+            let offset = text[idx..]
+                .trim_start_matches('+')
+                .trim()
+                .parse::<MemoryCell>()
+                .map_err(|_| AssembleError::InvalidOperand { line })?;
+            (text[..idx].trim(), offset)
+        }
+        None => (text, 0),
+    };
+
+    if !is_identifier(label) {
+        return Err(AssembleError::InvalidOperand { line });
+    }
+
+    Ok(Value::Label(label.to_string(), offset))
+}
+
+fn parse_operand(text: &str, line: usize) -> AssembleResult<Operand> {
+    let text = text.trim();
+
+    if let Some(rest) = text.strip_prefix('#') {
+        return Ok(Operand {
+            mode: Mode::Immediate,
+            value: parse_value(rest, line)?,
+        });
+    }
+
+    let inner = text
+        .strip_prefix('[')
+        .and_then(|t| t.strip_suffix(']'))
+        .ok_or(AssembleError::InvalidOperand { line })?
+        .trim();
+
+    match inner.strip_prefix("rb") {
+        Some(offset) if offset.is_empty() || offset.starts_with(['+', '-', ' ']) => {
+            let offset = offset.trim().trim_start_matches('+').trim();
+            let value = match offset {
+                "" => Value::Number(0),
+                _ => parse_value(offset, line)?,
+            };
+            Ok(Operand {
+                mode: Mode::Relative,
+                value,
+            })
+        }
+        _ => Ok(Operand {
+            mode: Mode::Position,
+            value: parse_value(inner, line)?,
+        }),
+    }
+}
+
+fn parse_operands(text: &str, line: usize) -> AssembleResult<Vec<Operand>> {
+    match text.trim() {
+        "" => Ok(Vec::new()),
+        t => t.split(',').map(|o| parse_operand(o, line)).collect(),
+    }
+}
+
+fn immediate(value: MemoryCell) -> Operand {
+    Operand {
+        mode: Mode::Immediate,
+        value: Value::Number(value),
+    }
+}
+
+fn stack_top() -> Operand {
+    Operand {
+        mode: Mode::Relative,
+        value: Value::Number(0),
+    }
+}
+
+fn parse_statement(mnemonic: &str, rest: &str, line: usize) -> AssembleResult<Vec<Item>> {
+    let single_operand = || {
+        let mut operands = parse_operands(rest, line)?;
+        match operands.len() {
+            1 => Ok(operands.remove(0)),
+            _ => Err(AssembleError::WrongOperandCount { line }),
+        }
+    };
+
+    match mnemonic {
+        "data" => Ok(vec![Item::Data(
+            rest.split(',')
+                .map(|v| parse_value(v, line))
+                .collect::<AssembleResult<_>>()?,
+        )]),
+        "push" => {
+            let value = single_operand()?;
+            Ok(vec![
+                Item::Instruction(OpCode::Add, vec![value, immediate(0), stack_top()]),
+                Item::Instruction(OpCode::SetRelativeBase, vec![immediate(1)]),
+            ])
+        }
+        "pop" => {
+            let target = single_operand()?;
+            if target.mode == Mode::Immediate {
+                return Err(AssembleError::OutputOperandInImmediateMode { line });
+            }
+            Ok(vec![
+                Item::Instruction(OpCode::SetRelativeBase, vec![immediate(-1)]),
+                Item::Instruction(OpCode::Add, vec![stack_top(), immediate(0), target]),
+            ])
+        }
+        _ => {
+            let op_code =
+                OpCode::from_mnemonic(mnemonic).ok_or(AssembleError::UnknownMnemonic { line })?;
+            let parameter_types = op_code.parameter_types();
+            let operands = parse_operands(rest, line)?;
+
+            if operands.len() != parameter_types.total_cells() {
+                return Err(AssembleError::WrongOperandCount { line });
+            }
+
+            if parameter_types.has_output_parameter
+                && operands.last().map(|o| o.mode) == Some(Mode::Immediate)
+            {
+                return Err(AssembleError::OutputOperandInImmediateMode { line });
+            }
+
+            Ok(vec![Item::Instruction(op_code, operands)])
+        }
+    }
+}
+
+fn parse_line(text: &str, line: usize) -> AssembleResult<Vec<Item>> {
+    let mut text = text.split(';').next().unwrap_or("").trim();
+    let mut items = Vec::new();
+
+    // Peel off any labels at the start of the line
+    while let Some(idx) = text.find(':') {
+        let label = text[..idx].trim();
+        if let Ok(address) = label.parse::<MemoryPointer>() {
+            items.push(Item::Address(address));
+        } else if is_identifier(label) {
+            items.push(Item::Label(label.to_string()));
+        } else {
+            return Err(AssembleError::InvalidLabel { line });
+        }
+        text = text[(idx + 1)..].trim();
+    }
+
+    if !text.is_empty() {
+        let (mnemonic, rest) = text.split_at(text.find(char::is_whitespace).unwrap_or(text.len()));
+        items.extend(parse_statement(mnemonic, rest, line)?);
+    }
+
+    Ok(items)
+}
+
+fn resolve(
+    value: &Value,
+    labels: &HashMap<String, MemoryPointer>,
+    line: usize,
+) -> AssembleResult<MemoryCell> {
+    match value {
+        Value::Number(n) => Ok(*n),
+        Value::Label(name, offset) => {
+            let address = labels
+                .get(name)
+                .ok_or(AssembleError::UndefinedLabel { line })?;
+            Ok(*address as MemoryCell + offset)
+        }
+    }
+}
+
+pub fn assemble(source: &str) -> AssembleResult<Vec<MemoryCell>> {
+    let mut items: Vec<(usize, Item)> = Vec::new();
+    for (idx, text) in source.lines().enumerate() {
+        let line = idx + 1;
+        items.extend(parse_line(text, line)?.into_iter().map(|item| (line, item)));
+    }
+
+    // First pass: find the address of every label
+    let mut labels: HashMap<String, MemoryPointer> = HashMap::new();
+    let mut address: MemoryPointer = 0;
+    for (line, item) in &items {
+        match item {
+            Item::Label(name) if labels.contains_key(name) => {
+                return Err(AssembleError::DuplicateLabel { line: *line });
+            }
+            Item::Label(name) => {
+                labels.insert(name.clone(), address);
+            }
+            Item::Address(expected) if *expected != address => {
+                return Err(AssembleError::AddressMismatch { line: *line });
+            }
+            _ => (),
+        }
+        address += item.size();
+    }
+
+    // Second pass: encode everything
+    let mut program: Vec<MemoryCell> = Vec::with_capacity(address);
+    for (line, item) in &items {
+        let line = *line;
+        match item {
+            Item::Instruction(op_code, operands) => {
+                let modes = operands
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, o)| o.mode.digit() * 10i64.pow(2 + idx as u32))
+                    .sum::<MemoryCell>();
+                program.push(op_code.number() + modes);
+
+                for operand in operands {
+                    let value = resolve(&operand.value, &labels, line)?;
+                    if operand.mode == Mode::Position && value < 0 {
+                        return Err(AssembleError::InvalidOperand { line });
+                    }
+                    program.push(value);
+                }
+            }
+            Item::Data(values) => {
+                for value in values {
+                    program.push(resolve(value, &labels, line)?);
+                }
+            }
+            Item::Label(_) | Item::Address(_) => (),
+        }
+    }
+
+    Ok(program)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use super::*;
+
+    fn run(program: Vec<MemoryCell>, inputs: &[MemoryCell]) -> Vec<MemoryCell> {
+        let mut computer = Computer::new(
+            PagedStorage::from(program),
+            BufferInput::new(inputs.len()),
+            BufferOutput::new(1),
+        );
+        computer.input().queue_many(inputs);
+        computer.run_until_halt().unwrap();
+        computer.output().pop_all()
+    }
+
+    #[test]
+    fn encodes_addressing_modes() {
+        assert_eq!(
+            vec![1002, 4, 3, 4, 33],
+            assemble("mul [4], #3, [4]\ndata 33").unwrap()
+        );
+        assert_eq!(
+            vec![109, 1, 204, -1, 99],
+            assemble("arb #1\nout [rb-1]\nhlt").unwrap()
+        );
+        assert_eq!(vec![21101, 1, 2, 0], assemble("add #1, #2, [rb]").unwrap());
+    }
+
+    #[test]
+    fn resolves_labels() {
+        let program = assemble(
+            "
+                    in   [value]
+                    mul  [value], #2, [value]
+                    out  [value]
+                    hlt
+            value:  data 0
+            ",
+        )
+        .unwrap();
+        assert_eq!(vec![3, 9, 1002, 9, 2, 9, 4, 9, 99, 0], program);
+        assert_eq!(vec![42], run(program, &[21]));
+    }
+
+    #[test]
+    fn runs_a_loop() {
+        // Count down from the input, printing each value
+        let program = assemble(
+            "
+                    in   [n]
+            loop:   out  [n]
+                    add  [n], #-1, [n]
+                    jt   [n], #loop
+                    hlt
+            n:      data 0
+            ",
+        )
+        .unwrap();
+        assert_eq!(vec![3, 2, 1], run(program, &[3]));
+    }
+
+    #[test]
+    fn push_and_pop_use_the_relative_base() {
+        let program = assemble(
+            "
+                    arb  #stack
+                    push #7
+                    push #8
+                    pop  [a]
+                    pop  [b]
+                    out  [a]
+                    out  [b]
+                    hlt
+            a:      data 0
+            b:      data 0
+            stack:
+            ",
+        )
+        .unwrap();
+        assert_eq!(vec![8, 7], run(program, &[]));
+    }
+
+    #[test]
+    fn label_offsets_and_address_checks() {
+        assert_eq!(
+            vec![4, 4, 99, 10, 20, 3],
+            assemble("out [x+1]\n2: hlt\nx: data 10, 20, x").unwrap()
+        );
+        assert_eq!(
+            Err(AssembleError::AddressMismatch { line: 2 }),
+            assemble("hlt\n0: hlt")
+        );
+    }
+
+    #[test]
+    fn reports_errors_with_line_numbers() {
+        assert_eq!(
+            Err(AssembleError::UnknownMnemonic { line: 2 }),
+            assemble("hlt\nfoo [1]")
+        );
+        assert_eq!(
+            Err(AssembleError::WrongOperandCount { line: 1 }),
+            assemble("add [1], [2]")
+        );
+        assert_eq!(
+            Err(AssembleError::OutputOperandInImmediateMode { line: 1 }),
+            assemble("add [1], [2], #3")
+        );
+        assert_eq!(
+            Err(AssembleError::UndefinedLabel { line: 1 }),
+            assemble("jt #1, #nowhere")
+        );
+        assert_eq!(
+            Err(AssembleError::DuplicateLabel { line: 2 }),
+            assemble("a: hlt\na: hlt")
+        );
+        assert_eq!(
+            Err(AssembleError::InvalidOperand { line: 1 }),
+            assemble("out 5")
+        );
+    }
+
+    #[test]
+    fn every_op_code_round_trips() {
+        for op_code in OpCode::ALL.iter() {
+            let parameter_types = op_code.parameter_types();
+            let mut operands: Vec<&str> = vec!["[rb-3]", "#5", "[7]"];
+            operands.truncate(parameter_types.total_cells());
+            if parameter_types.has_output_parameter {
+                operands.pop();
+                operands.push("[9]");
+            }
+            let source = format!("{} {}", op_code.mnemonic(), operands.join(", "));
+            let program = assemble(&source).unwrap();
+            assert_eq!(
+                program,
+                assemble(&disassemble(&program).to_string()).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn puzzle_inputs_round_trip_through_the_disassembler() {
+        for day in [2, 5, 7, 9, 11, 13, 15, 17] {
+            let program = load_program(&format!("inputs/day{}.txt", day));
+            let listing = disassemble(&program).to_string();
+            assert_eq!(program, assemble(&listing).unwrap());
+        }
+    }
+}
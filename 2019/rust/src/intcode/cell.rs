@@ -0,0 +1,144 @@
+use super::MemoryPointer;
+use num::traits::{CheckedAdd, CheckedMul, NumCast, PrimInt, Signed};
+use std::convert::TryInto;
+use std::fmt::{self, Debug, Display};
+use std::hash::Hash;
+use std::str::FromStr;
+
+// A value which can be held in a single cell of Intcode memory.
+// Arithmetic is always checked, so that overflow can be reported rather than wrapping.
+pub trait IntCodeCell:
+    Copy + Eq + Ord + Hash + Debug + Display + FromStr + Default + Send + Sync + 'static
+{
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    fn from_i64(value: i64) -> Option<Self>;
+    fn to_i64(self) -> Option<i64>;
+    // Every cell fits in an i128, which is used to report values in errors
+    fn to_i128(self) -> i128;
+
+    fn to_pointer(self) -> Option<MemoryPointer> {
+        self.to_i64()?.try_into().ok()
+    }
+
+    fn zero() -> Self {
+        Self::default()
+    }
+
+    fn one() -> Self {
+        Self::from_i64(1).expect("Every cell can hold a one")
+    }
+}
+
+macro_rules! primitive_cell {
+    ($t:ty) => {
+        impl IntCodeCell for $t {
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$t>::checked_add(self, other)
+            }
+
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                <$t>::checked_mul(self, other)
+            }
+
+            fn from_i64(value: i64) -> Option<Self> {
+                value.try_into().ok()
+            }
+
+            fn to_i64(self) -> Option<i64> {
+                self.try_into().ok()
+            }
+
+            fn to_i128(self) -> i128 {
+                self.into()
+            }
+        }
+    };
+}
+
+primitive_cell!(i64);
+primitive_cell!(i128);
+
+// Use any other signed integer as a cell, such as a narrow one to test for overflow
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Checked<T>(pub T);
+
+impl<T: Display> Display for Checked<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T: FromStr> FromStr for Checked<T> {
+    type Err = T::Err;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Checked(s.parse()?))
+    }
+}
+
+impl<T> IntCodeCell for Checked<T>
+where
+    T: PrimInt + Signed + Hash + Debug + Display + FromStr + Default + Send + Sync + 'static,
+{
+    fn checked_add(self, other: Self) -> Option<Self> {
+        CheckedAdd::checked_add(&self.0, &other.0).map(Checked)
+    }
+
+    fn checked_mul(self, other: Self) -> Option<Self> {
+        CheckedMul::checked_mul(&self.0, &other.0).map(Checked)
+    }
+
+    fn from_i64(value: i64) -> Option<Self> {
+        <T as NumCast>::from(value).map(Checked)
+    }
+
+    fn to_i64(self) -> Option<i64> {
+        self.0.to_i64()
+    }
+
+    fn to_i128(self) -> i128 {
+        self.0.to_i128().expect("Signed primitives fit in an i128")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+
+    // Square the input, then add one to it, and output the result
+    fn square_plus_one<C: IntCodeCell>(value: C) -> IntCodeResult<C> {
+        let source = "in [x]\nmul [x], [x], [x]\nadd [x], #1, [x]\nout [x]\nhlt\nx: data 0";
+        let program: Vec<C> = assemble(source)
+            .unwrap()
+            .into_iter()
+            .map(|v| C::from_i64(v).unwrap())
+            .collect();
+        let mut computer = Computer::new(program, BufferInput::new(1), BufferOutput::new(1));
+        computer.input().queue(value);
+        computer.run_until_halt()?;
+        Ok(computer.output().pop().unwrap())
+    }
+
+    #[test]
+    fn runs_with_wider_cells() {
+        assert_eq!(10, square_plus_one(3i64).unwrap());
+        assert!(matches!(
+            square_plus_one(1i64 << 32),
+            Err(e) if e.kind() == IntCodeErrorKind::ArithmeticOverflow
+        ));
+        assert_eq!((1i128 << 64) + 1, square_plus_one(1i128 << 32).unwrap());
+    }
+
+    #[test]
+    fn checked_cells_report_overflow() {
+        assert_eq!(Checked(32401), square_plus_one(Checked(180i16)).unwrap());
+        assert!(matches!(
+            square_plus_one(Checked(182i16)),
+            Err(e) if e.kind() == IntCodeErrorKind::ArithmeticOverflow
+        ));
+        assert_eq!(Checked(32762), square_plus_one(Checked(181i16)).unwrap());
+        assert_eq!(None, Checked::<i8>::from_i64(1000));
+        assert_eq!(Ok(Checked(-5i8)), "-5".parse());
+    }
+}
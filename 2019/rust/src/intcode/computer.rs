@@ -1,56 +1,70 @@
+use super::decode::decode_with;
 use super::io::{InputSource, OutputSink};
-use super::storage::Storage;
-use super::{IntCodeError, IntCodeResult, MemoryCell, MemoryPointer};
-use std::collections::HashMap;
-use std::convert::TryInto;
-use std::io::Write;
-
-pub struct Computer<S: Storage, I: InputSource, O: OutputSink> {
+use super::snapshot::Snapshot;
+use super::storage::{PagedStorage, Storage};
+use super::tracer::{NoTracer, Tracer};
+use super::{
+    IntCodeCell, IntCodeError, IntCodeErrorKind, IntCodeResult, MemoryCell, MemoryPointer,
+};
+use std::collections::HashSet;
+
+pub struct Computer<S, I, O, T = NoTracer, C = MemoryCell>
+where
+    C: IntCodeCell,
+    S: Storage<C>,
+    I: InputSource<C>,
+    O: OutputSink<C>,
+    T: Tracer<C>,
+{
     state: S,
     program_counter: MemoryPointer,
-    relative_base: MemoryCell,
+    relative_base: C,
     input: I,
     output: O,
     has_halted: bool,
-    extra_memory: Option<HashMap<MemoryPointer, MemoryCell>>,
+    breakpoints: HashSet<MemoryPointer>,
+    watchpoints: HashSet<MemoryPointer>,
+    last_write: Option<MemoryPointer>,
+    decode_cache: Option<Vec<Option<Operation<C>>>>,
+    instruction_count: usize,
+    instruction_budget: Option<usize>,
+    tracer: T,
 }
 
+#[derive(Debug, PartialEq)]
 pub enum StepResult {
     Halt,
     Continue,
     WaitingOnInput,
-}
-
-fn debug_log(args: std::fmt::Arguments) {
-    let mut output = std::io::sink();
-    //let mut output = std::io::stdout();
-    writeln!(&mut output, "{}", args).expect("Failed to write debug log");
+    Breakpoint(MemoryPointer),
+    Watchpoint(MemoryPointer),
+    BudgetExhausted,
 }
 
 #[derive(Debug)]
-enum Effect {
+enum Effect<C> {
     NoOp,
-    StoreValue(MemoryCell),
-    OutputValue(MemoryCell),
+    StoreValue(C),
+    OutputValue(C),
     Jump(MemoryPointer),
-    SetRelativeBase(MemoryCell),
+    SetRelativeBase(C),
 }
 
-#[derive(Debug, PartialEq)]
-enum Parameter {
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Parameter<C = MemoryCell> {
     Position(MemoryPointer),
-    Immediate(MemoryCell),
-    Relative(MemoryCell),
+    Immediate(C),
+    Relative(C),
 }
 
-#[derive(Debug)]
-struct ParameterTypes {
-    inputs: usize,
-    has_output_parameter: bool,
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub(super) struct ParameterTypes {
+    pub(super) inputs: usize,
+    pub(super) has_output_parameter: bool,
 }
 
 impl ParameterTypes {
-    fn total_cells(&self) -> MemoryPointer {
+    pub(super) fn total_cells(&self) -> MemoryPointer {
         match self.has_output_parameter {
             true => self.inputs + 1,
             false => self.inputs,
@@ -58,8 +72,8 @@ impl ParameterTypes {
     }
 }
 
-#[derive(Debug, PartialEq)]
-enum OpCode {
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum OpCode {
     Add,
     Multiply,
     Input,
@@ -73,7 +87,25 @@ enum OpCode {
 }
 
 impl OpCode {
-    fn from_digits(digits: &str) -> IntCodeResult<OpCode> {
+    pub(super) fn from_number(value: MemoryCell) -> IntCodeResult<OpCode> {
+        Ok(match value {
+            1 => OpCode::Add,
+            2 => OpCode::Multiply,
+            3 => OpCode::Input,
+            4 => OpCode::Output,
+            5 => OpCode::JumpIfTrue,
+            6 => OpCode::JumpIfFalse,
+            7 => OpCode::LessThan,
+            8 => OpCode::Equals,
+            9 => OpCode::SetRelativeBase,
+            99 => OpCode::Halt,
+            _ => {
+                return Err(IntCodeErrorKind::UnknownOpCode.into());
+            }
+        })
+    }
+
+    pub(super) fn from_digits(digits: &str) -> IntCodeResult<OpCode> {
         Ok(match digits {
             "01" => OpCode::Add,
             "02" => OpCode::Multiply,
@@ -86,12 +118,62 @@ impl OpCode {
             "09" => OpCode::SetRelativeBase,
             "99" => OpCode::Halt,
             _ => {
-                return Err(IntCodeError::UnknownOpCode);
+                return Err(IntCodeErrorKind::UnknownOpCode.into());
             }
         })
     }
 
-    fn parameter_types(&self) -> ParameterTypes {
+    pub(super) const ALL: [OpCode; 10] = [
+        OpCode::Add,
+        OpCode::Multiply,
+        OpCode::Input,
+        OpCode::Output,
+        OpCode::JumpIfTrue,
+        OpCode::JumpIfFalse,
+        OpCode::LessThan,
+        OpCode::Equals,
+        OpCode::SetRelativeBase,
+        OpCode::Halt,
+    ];
+
+    pub(super) fn number(&self) -> MemoryCell {
+        match self {
+            OpCode::Add => 1,
+            OpCode::Multiply => 2,
+            OpCode::Input => 3,
+            OpCode::Output => 4,
+            OpCode::JumpIfTrue => 5,
+            OpCode::JumpIfFalse => 6,
+            OpCode::LessThan => 7,
+            OpCode::Equals => 8,
+            OpCode::SetRelativeBase => 9,
+            OpCode::Halt => 99,
+        }
+    }
+
+    pub(super) fn from_mnemonic(mnemonic: &str) -> Option<OpCode> {
+        OpCode::ALL
+            .iter()
+            .find(|op| op.mnemonic() == mnemonic)
+            .copied()
+    }
+
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            OpCode::Add => "add",
+            OpCode::Multiply => "mul",
+            OpCode::Input => "in",
+            OpCode::Output => "out",
+            OpCode::JumpIfTrue => "jt",
+            OpCode::JumpIfFalse => "jf",
+            OpCode::LessThan => "lt",
+            OpCode::Equals => "eq",
+            OpCode::SetRelativeBase => "arb",
+            OpCode::Halt => "hlt",
+        }
+    }
+
+    pub(super) fn parameter_types(&self) -> ParameterTypes {
         match self {
             OpCode::Add => ParameterTypes {
                 inputs: 2,
@@ -137,41 +219,127 @@ impl OpCode {
     }
 }
 
-#[derive(Debug)]
-struct Operation {
-    op_code: OpCode,
-    parameter_types: ParameterTypes,
-    inputs: Vec<Parameter>,
-    output: Option<Parameter>,
+// The longest instruction is an op code followed by three parameters
+pub(super) const MAX_INSTRUCTION_LENGTH: MemoryPointer = 4;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Operation<C = MemoryCell> {
+    pub(super) op_code: OpCode,
+    pub(super) parameter_types: ParameterTypes,
+    // Only the first `parameter_types.inputs` of these are meaningful
+    pub(super) inputs: [Parameter<C>; 2],
+    pub(super) output: Option<Parameter<C>>,
 }
 
-fn cast_cell_to_pointer(value: MemoryCell) -> IntCodeResult<MemoryPointer> {
-    Ok(value
-        .try_into()
-        .map_err(|_| IntCodeError::MemoryCellIsInvalidPointer)?)
+pub(super) fn cast_cell_to_pointer<C: IntCodeCell>(value: C) -> IntCodeResult<MemoryPointer> {
+    value.to_pointer().ok_or_else(|| {
+        IntCodeError::new(IntCodeErrorKind::MemoryCellIsInvalidPointer).with_value(value)
+    })
 }
 
-impl Operation {
+impl<C: IntCodeCell> Operation<C> {
+    pub fn op_code(&self) -> OpCode {
+        self.op_code
+    }
+
+    pub fn inputs(&self) -> &[Parameter<C>] {
+        &self.inputs[..self.parameter_types.inputs]
+    }
+
+    pub fn output(&self) -> Option<&Parameter<C>> {
+        self.output.as_ref()
+    }
+
     fn get_program_counter_increment(&self) -> MemoryPointer {
         1 + self.parameter_types.total_cells()
     }
 }
 
-impl<S: Storage, I: InputSource, O: OutputSink> Computer<S, I, O> {
-    pub fn new(state: S, input: I, output: O) -> Computer<S, I, O> {
+impl<C, S, I, O> Computer<S, I, O, NoTracer, C>
+where
+    C: IntCodeCell,
+    S: Storage<C>,
+    I: InputSource<C>,
+    O: OutputSink<C>,
+{
+    pub fn new(state: S, input: I, output: O) -> Computer<S, I, O, NoTracer, C> {
         Computer {
             state: state,
             program_counter: 0,
-            relative_base: 0,
+            relative_base: C::zero(),
             has_halted: false,
             input,
             output,
-            extra_memory: None,
+            breakpoints: HashSet::new(),
+            watchpoints: HashSet::new(),
+            last_write: None,
+            decode_cache: None,
+            instruction_count: 0,
+            instruction_budget: None,
+            tracer: NoTracer,
         }
     }
+}
+
+impl<C, I, O> Computer<PagedStorage<C>, I, O, NoTracer, C>
+where
+    C: IntCodeCell,
+    I: InputSource<C>,
+    O: OutputSink<C>,
+{
+    // Only the pages the snapshot has something in are allocated
+    pub fn from_snapshot(
+        snapshot: &Snapshot<C>,
+        input: I,
+        output: O,
+    ) -> Computer<PagedStorage<C>, I, O, NoTracer, C> {
+        let storage = PagedStorage::with_limit(snapshot.limit);
+        let mut computer = Computer::new(storage, input, output);
+        computer
+            .restore(snapshot)
+            .expect("Snapshot memory fits in its own storage");
+        computer
+    }
+}
+
+impl<C, S, I, O, T> Computer<S, I, O, T, C>
+where
+    C: IntCodeCell,
+    S: Storage<C>,
+    I: InputSource<C>,
+    O: OutputSink<C>,
+    T: Tracer<C>,
+{
+    pub fn with_tracer<U: Tracer<C>>(self, tracer: U) -> Computer<S, I, O, U, C> {
+        Computer {
+            state: self.state,
+            program_counter: self.program_counter,
+            relative_base: self.relative_base,
+            has_halted: self.has_halted,
+            input: self.input,
+            output: self.output,
+            breakpoints: self.breakpoints,
+            watchpoints: self.watchpoints,
+            last_write: self.last_write,
+            decode_cache: self.decode_cache,
+            instruction_count: self.instruction_count,
+            instruction_budget: self.instruction_budget,
+            tracer,
+        }
+    }
+
+    pub fn tracer(&mut self) -> &mut T {
+        &mut self.tracer
+    }
+
+    pub fn into_tracer(self) -> T {
+        self.tracer
+    }
 
-    pub fn enable_extra_memory(&mut self) {
-        self.extra_memory = Some(HashMap::new());
+    // Remember decoded operations by address, so that loops don't decode the same instruction
+    // over and over. Writes made by the program invalidate any instruction they overlap.
+    pub fn enable_decode_cache(&mut self) {
+        self.decode_cache = Some(vec![None; self.state.size()]);
     }
 
     pub fn input(&mut self) -> &mut I {
@@ -183,6 +351,10 @@ impl<S: Storage, I: InputSource, O: OutputSink> Computer<S, I, O> {
     }
 
     pub fn state(&mut self) -> &mut S {
+        // We can't see what the caller changes, so forget everything we've decoded
+        if let Some(cache) = &mut self.decode_cache {
+            cache.iter_mut().for_each(|op| *op = None);
+        }
         &mut self.state
     }
 
@@ -190,67 +362,228 @@ impl<S: Storage, I: InputSource, O: OutputSink> Computer<S, I, O> {
         self.has_halted
     }
 
-    fn increment_for_operation(&mut self, operation: &Operation) {
+    pub fn program_counter(&self) -> MemoryPointer {
+        self.program_counter
+    }
+
+    pub fn relative_base(&self) -> C {
+        self.relative_base
+    }
+
+    // How many instructions have been executed, including the final halt
+    pub fn instruction_count(&self) -> usize {
+        self.instruction_count
+    }
+
+    // Allow at most this many more instructions to execute, or None for no limit.
+    // Once the budget is spent, step and resume return BudgetExhausted.
+    pub fn set_instruction_budget(&mut self, budget: Option<usize>) {
+        self.instruction_budget = budget;
+    }
+
+    pub fn instruction_budget(&self) -> Option<usize> {
+        self.instruction_budget
+    }
+
+    pub fn storage(&self) -> &S {
+        &self.state
+    }
+
+    pub fn read_memory(&self, index: MemoryPointer) -> IntCodeResult<C> {
+        self.get_memory_at(index)
+    }
+
+    pub fn snapshot(&self) -> Snapshot<C> {
+        Snapshot {
+            size: self.state.size(),
+            limit: self.state.limit(),
+            memory: self
+                .state
+                .regions()
+                .into_iter()
+                .filter_map(|(start, cells)| {
+                    // Trim the zeroes from either end, dropping the run if it's all zero
+                    let first = cells.iter().position(|c| *c != C::zero())?;
+                    let last = cells.iter().rposition(|c| *c != C::zero())?;
+                    Some((start + first, cells[first..=last].to_vec()))
+                })
+                .collect(),
+            program_counter: self.program_counter,
+            relative_base: self.relative_base,
+            has_halted: self.has_halted,
+        }
+    }
+
+    // Put the computer back in to the state it was in when the snapshot was taken.
+    // Breakpoints, watchpoints, input and output are left alone.
+    // Any memory the snapshot doesn't hold is cleared back to zero.
+    pub fn restore(&mut self, snapshot: &Snapshot<C>) -> IntCodeResult<()> {
+        let end = snapshot.size;
+        if end > 0 && self.state.get(end - 1).is_err() {
+            return Err(
+                IntCodeError::new(IntCodeErrorKind::SnapshotSizeMismatch).with_address(end - 1)
+            );
+        }
+
+        self.state.clear_from(0)?;
+        for (start, cells) in &snapshot.memory {
+            self.write_cells(*start, cells)?;
+        }
+        self.forget_memory();
+        self.program_counter = snapshot.program_counter;
+        self.relative_base = snapshot.relative_base;
+        self.has_halted = snapshot.has_halted;
+        Ok(())
+    }
+
+    // Start over with a fresh copy of the program. This is much cheaper than building a new
+    // Computer, and as with restore, breakpoints, watchpoints, input and output are left alone.
+    pub fn reset(&mut self, program: &[C]) -> IntCodeResult<()> {
+        self.load_memory(program)?;
+        self.program_counter = 0;
+        self.relative_base = C::zero();
+        self.has_halted = false;
+        self.instruction_count = 0;
+        Ok(())
+    }
+
+    fn load_memory(&mut self, memory: &[C]) -> IntCodeResult<()> {
+        self.write_cells(0, memory)?;
+        self.state.clear_from(memory.len())?;
+        self.forget_memory();
+        Ok(())
+    }
+
+    fn write_cells(&mut self, start: MemoryPointer, cells: &[C]) -> IntCodeResult<()> {
+        for (idx, value) in cells.iter().enumerate() {
+            self.state.put(start + idx, *value)?;
+        }
+        Ok(())
+    }
+
+    // Anything remembered about the old memory is stale once it has all been replaced
+    fn forget_memory(&mut self) {
+        self.last_write = None;
+        if let Some(cache) = &mut self.decode_cache {
+            cache.iter_mut().for_each(|op| *op = None);
+        }
+    }
+
+    // Copy out every cell the storage currently holds
+    pub fn dump_memory(&self) -> Vec<C> {
+        (0..self.state.size())
+            .map(|i| self.state.get(i).unwrap_or_default())
+            .collect()
+    }
+
+    pub fn breakpoints(&self) -> &HashSet<MemoryPointer> {
+        &self.breakpoints
+    }
+
+    // Returns true if the breakpoint was added, false if it already existed
+    pub fn add_breakpoint(&mut self, at: MemoryPointer) -> bool {
+        self.breakpoints.insert(at)
+    }
+
+    pub fn remove_breakpoint(&mut self, at: MemoryPointer) -> bool {
+        self.breakpoints.remove(&at)
+    }
+
+    pub fn watchpoints(&self) -> &HashSet<MemoryPointer> {
+        &self.watchpoints
+    }
+
+    // Returns true if the watchpoint was added, false if it already existed
+    pub fn add_watchpoint(&mut self, at: MemoryPointer) -> bool {
+        self.watchpoints.insert(at)
+    }
+
+    pub fn remove_watchpoint(&mut self, at: MemoryPointer) -> bool {
+        self.watchpoints.remove(&at)
+    }
+
+    fn increment_for_operation(&mut self, operation: &Operation<C>) {
         let increment = operation.get_program_counter_increment();
         self.program_counter += increment;
     }
 
-    fn get_input(&mut self) -> Option<MemoryCell> {
+    fn get_input(&mut self) -> Option<C> {
         let result = self.input.next();
-        match &result {
-            Some(v) => debug_log(format_args!("Read input: {}", *v)),
-            None => debug_log(format_args!("No input available")),
+        if let Some(v) = result {
+            self.tracer.input(self.instruction_count, v);
         }
 
         result
     }
 
-    fn put_output(&mut self, value: MemoryCell) {
-        debug_log(format_args!("Writing output: {}", value));
+    fn put_output(&mut self, value: C) {
+        self.tracer.output(self.instruction_count, value);
         self.output.write(value)
     }
 
-    fn resolve_parameter_address(&self, parameter: &Parameter) -> IntCodeResult<MemoryPointer> {
+    fn resolve_parameter_address(&self, parameter: &Parameter<C>) -> IntCodeResult<MemoryPointer> {
         match parameter {
-            Parameter::Immediate(_) => Err(IntCodeError::OutputParameterInImmediateMode),
+            Parameter::Immediate(_) => Err(IntCodeErrorKind::OutputParameterInImmediateMode.into()),
             Parameter::Position(addr) => Ok(*addr),
-            Parameter::Relative(offset) => cast_cell_to_pointer(self.relative_base + *offset),
+            Parameter::Relative(offset) => cast_cell_to_pointer(
+                self.relative_base
+                    .checked_add(*offset)
+                    .ok_or(IntCodeErrorKind::ArithmeticOverflow)?,
+            ),
         }
     }
 
-    fn single_step(&mut self, operation: &Operation) -> IntCodeResult<StepResult> {
+    fn single_step(&mut self, operation: &Operation<C>) -> IntCodeResult<StepResult> {
         if let OpCode::Halt = operation.op_code {
             return Ok(StepResult::Halt);
         }
 
-        let get = |idx: usize| {
-            let param = operation
-                .inputs
-                .get(idx)
-                .ok_or(IntCodeError::InvalidParameterIndex)?;
-            match param {
-                Parameter::Immediate(val) => Ok(*val),
-                p => self.get_memory_at(self.resolve_parameter_address(p)?),
+        // Resolve the values of every input parameter up front
+        let mut values: [C; 2] = [C::zero(); 2];
+        for (idx, param) in operation.inputs().iter().enumerate() {
+            let value = match param {
+                Parameter::Immediate(val) => *val,
+                p => self.get_memory_at(self.resolve_parameter_address(p)?)?,
+            };
+            self.tracer.parameter(idx, value);
+            *values
+                .get_mut(idx)
+                .ok_or(IntCodeErrorKind::InvalidParameterIndex)? = value;
+        }
+
+        let get = |idx: usize| -> IntCodeResult<C> {
+            if idx < operation.parameter_types.inputs {
+                Ok(values[idx])
+            } else {
+                Err(IntCodeErrorKind::InvalidParameterIndex.into())
             }
         };
 
         let result = match operation.op_code {
-            OpCode::Add => Effect::StoreValue(get(0)? + get(1)?),
-            OpCode::Multiply => Effect::StoreValue(get(0)? * get(1)?),
+            OpCode::Add => Effect::StoreValue(
+                get(0)?
+                    .checked_add(get(1)?)
+                    .ok_or(IntCodeErrorKind::ArithmeticOverflow)?,
+            ),
+            OpCode::Multiply => Effect::StoreValue(
+                get(0)?
+                    .checked_mul(get(1)?)
+                    .ok_or(IntCodeErrorKind::ArithmeticOverflow)?,
+            ),
             OpCode::Input => match self.get_input() {
                 Some(v) => Effect::StoreValue(v),
                 None => return Ok(StepResult::WaitingOnInput),
             },
             OpCode::Output => Effect::OutputValue(get(0)?),
             OpCode::JumpIfTrue => {
-                if get(0)? == 0 {
+                if get(0)? == C::zero() {
                     Effect::NoOp
                 } else {
                     Effect::Jump(cast_cell_to_pointer(get(1)?)?)
                 }
             }
             OpCode::JumpIfFalse => {
-                if get(0)? == 0 {
+                if get(0)? == C::zero() {
                     Effect::Jump(cast_cell_to_pointer(get(1)?)?)
                 } else {
                     Effect::NoOp
@@ -258,24 +591,22 @@ impl<S: Storage, I: InputSource, O: OutputSink> Computer<S, I, O> {
             }
             OpCode::LessThan => {
                 if get(0)? < get(1)? {
-                    Effect::StoreValue(1)
+                    Effect::StoreValue(C::one())
                 } else {
-                    Effect::StoreValue(0)
+                    Effect::StoreValue(C::zero())
                 }
             }
             OpCode::Equals => {
                 if get(0)? == get(1)? {
-                    Effect::StoreValue(1)
+                    Effect::StoreValue(C::one())
                 } else {
-                    Effect::StoreValue(0)
+                    Effect::StoreValue(C::zero())
                 }
             }
             OpCode::SetRelativeBase => Effect::SetRelativeBase(get(0)?),
             _ => Effect::NoOp,
         };
 
-        debug_log(format_args!("Effect: {:?}", result));
-
         let effect_states = (result, &operation.output);
         match effect_states {
             (Effect::NoOp, None) => {
@@ -285,6 +616,7 @@ impl<S: Storage, I: InputSource, O: OutputSink> Computer<S, I, O> {
             (Effect::StoreValue(value), Some(output)) => {
                 let addr = self.resolve_parameter_address(&output)?;
                 self.set_memory_at(addr, value)?;
+                self.tracer.memory_write(addr, value);
                 self.increment_for_operation(operation);
                 Ok(StepResult::Continue)
             }
@@ -294,110 +626,100 @@ impl<S: Storage, I: InputSource, O: OutputSink> Computer<S, I, O> {
                 Ok(StepResult::Continue)
             }
             (Effect::Jump(addr), None) => {
+                self.tracer.jump(self.program_counter, addr);
                 self.program_counter = addr;
                 Ok(StepResult::Continue)
             }
             (Effect::SetRelativeBase(offset), None) => {
-                self.relative_base += offset;
+                let relative_base = self
+                    .relative_base
+                    .checked_add(offset)
+                    .ok_or(IntCodeErrorKind::ArithmeticOverflow)?;
+                self.tracer.relative_base(self.relative_base, relative_base);
+                self.relative_base = relative_base;
                 self.increment_for_operation(operation);
                 Ok(StepResult::Continue)
             }
-            _ => Err(IntCodeError::EffectMismatch),
+            _ => Err(IntCodeErrorKind::EffectMismatch.into()),
         }
     }
 
-    fn read_op(&self, from: MemoryPointer) -> IntCodeResult<Operation> {
-        let op = self.get_memory_at(from)?;
-        let digits = format!("{:05}", op);
-        assert_eq!(5, digits.len());
-
-        let op_code = OpCode::from_digits(&digits[3..5])?;
-        let parameter_types = op_code.parameter_types();
+    fn read_op(&mut self, from: MemoryPointer) -> IntCodeResult<Operation<C>> {
+        if let Some(Some(op)) = self.decode_cache.as_ref().and_then(|c| c.get(from)) {
+            return Ok(*op);
+        }
 
-        // Make a positional parameter by reading a memory location from the given index
-        let make_positional = |idx: usize| {
-            let location: MemoryPointer =
-                cast_cell_to_pointer(self.get_memory_at(from + 1 + idx)?)?;
-            Ok(Parameter::Position(location))
-        };
+        let op = decode_with(|at| self.get_memory_at(at), from)?;
 
-        // Make an immediate parameter by reading a value from the given index
-        let make_immediate = |idx: usize| {
-            let value = self.get_memory_at(from + 1 + idx)?;
-            Ok(Parameter::Immediate(value))
-        };
-
-        // Make a relative parameter by reading a memory offset from the given index
-        let make_relative = |idx: usize| {
-            let offset: MemoryCell = self.get_memory_at(from + 1 + idx)?;
-            Ok(Parameter::Relative(offset))
-        };
+        if let Some(slot) = self.decode_cache.as_mut().and_then(|c| c.get_mut(from)) {
+            *slot = Some(op);
+        }
 
-        // Build a parameter by determining its type and reading its location/value
-        let build_parameter = |idx: usize| {
-            if idx >= parameter_types.inputs + 1 {
-                return Err(IntCodeError::InvalidParameterIndex);
-            }
+        Ok(op)
+    }
 
-            let mode_digit = 2 - idx;
-            let mode = &digits[mode_digit..=mode_digit];
-            match mode {
-                "0" => make_positional(idx),
-                "1" => make_immediate(idx),
-                "2" => make_relative(idx),
-                _ => {
-                    return Err(IntCodeError::UnknownParameterType);
-                }
-            }
-        };
+    // Execute exactly one instruction, ignoring any breakpoints and watchpoints
+    pub fn step(&mut self) -> IntCodeResult<StepResult> {
+        if self.has_halted {
+            return Ok(StepResult::Halt);
+        }
 
-        let parameters = (0..parameter_types.inputs)
-            .map(build_parameter)
-            .collect::<IntCodeResult<Vec<_>>>()?;
+        if self.instruction_budget == Some(0) {
+            return Ok(StepResult::BudgetExhausted);
+        }
 
-        let output = if parameter_types.has_output_parameter {
-            // If we have an output parameter, find it immediately after the inputs
-            let param = build_parameter(parameter_types.inputs)?;
-            if let Parameter::Immediate(_) = param {
-                return Err(IntCodeError::OutputParameterInImmediateMode);
+        let program_counter = self.program_counter;
+        let (op, step) = self
+            .read_op(program_counter)
+            .and_then(|op| Ok((op, self.single_step(&op)?)))
+            .map_err(|e| self.locate_error(e))?;
+        match step {
+            // Blocking on input doesn't execute anything, we'll try again on the next step
+            StepResult::WaitingOnInput => {
+                self.tracer.blocked_on_input(self.program_counter);
+                return Ok(step);
             }
-            Some(param)
-        } else {
-            None
-        };
-
-        let operation = Operation {
-            op_code: op_code,
-            parameter_types: parameter_types,
-            inputs: parameters,
-            output: output,
-        };
-
-        debug_log(format_args!(
-            "Operation #{} [{}] is {:?}",
-            from, digits, operation
-        ));
+            StepResult::Halt => self.has_halted = true,
+            _ => (),
+        }
 
-        Ok(operation)
+        self.tracer.operation(program_counter, &op);
+        self.instruction_count += 1;
+        if let Some(budget) = &mut self.instruction_budget {
+            *budget -= 1;
+        }
+        Ok(step)
     }
 
+    // Run until the computer halts, blocks on input, hits a breakpoint or watchpoint,
+    // or runs out of instruction budget.
+    // A breakpoint on the instruction we're resuming from is ignored, so that
+    // resuming after a breakpoint makes progress.
     pub fn resume(&mut self) -> IntCodeResult<StepResult> {
         if self.has_halted {
             return Ok(StepResult::Halt);
         }
 
+        let mut is_first = true;
         loop {
-            let op = self.read_op(self.program_counter)?;
-            let step = self.single_step(&op)?;
-            match step {
-                StepResult::Halt => {
-                    self.has_halted = true;
-                    return Ok(StepResult::Halt);
-                }
-                StepResult::WaitingOnInput => {
-                    return Ok(StepResult::WaitingOnInput);
-                }
+            if !is_first
+                && !self.breakpoints.is_empty()
+                && self.breakpoints.contains(&self.program_counter)
+            {
+                return Ok(StepResult::Breakpoint(self.program_counter));
+            }
+            is_first = false;
+
+            self.last_write = None;
+            match self.step()? {
                 StepResult::Continue => (),
+                other => return Ok(other),
+            }
+
+            if let Some(addr) = self.last_write {
+                if !self.watchpoints.is_empty() && self.watchpoints.contains(&addr) {
+                    return Ok(StepResult::Watchpoint(addr));
+                }
             }
         }
     }
@@ -409,35 +731,209 @@ impl<S: Storage, I: InputSource, O: OutputSink> Computer<S, I, O> {
             }
             StepResult::WaitingOnInput => panic!("Computer is blocked on input"),
             StepResult::Continue => panic!("Resume stopped on continue"),
+            StepResult::Breakpoint(_) | StepResult::Watchpoint(_) => {
+                panic!("Computer stopped on a breakpoint")
+            }
+            StepResult::BudgetExhausted => {
+                Err(self.locate_error(IntCodeErrorKind::InstructionBudgetExhausted.into()))
+            }
         }
     }
 
-    fn get_memory_at(&self, index: MemoryPointer) -> IntCodeResult<MemoryCell> {
-        if index + 1 > self.state.size() {
-            match &self.extra_memory {
-                Some(mem) => {
-                    let value = *mem.get(&index).unwrap_or(&0);
-                    Ok(value)
-                }
-                None => Err(IntCodeError::ReadMemoryOutOfBounds),
+    // Say which instruction was executing when the error happened
+    fn locate_error(&self, error: IntCodeError) -> IntCodeError {
+        let instruction = self.state.get(self.program_counter).ok();
+        error.at_instruction(self.program_counter, instruction)
+    }
+
+    fn get_memory_at(&self, index: MemoryPointer) -> IntCodeResult<C> {
+        self.state.get(index)
+    }
+
+    fn set_memory_at(&mut self, index: MemoryPointer, value: C) -> IntCodeResult<()> {
+        self.last_write = Some(index);
+
+        if let Some(cache) = &mut self.decode_cache {
+            let first = index.saturating_sub(MAX_INSTRUCTION_LENGTH - 1);
+            for op in cache.iter_mut().take(index + 1).skip(first) {
+                *op = None;
             }
-        } else {
-            Ok(self.state.get(index))
         }
+        self.state.put(index, value)
     }
+}
 
-    fn set_memory_at(&mut self, index: MemoryPointer, value: MemoryCell) -> IntCodeResult<()> {
-        if index + 1 > self.state.size() {
-            match &mut self.extra_memory {
-                Some(mem) => {
-                    mem.entry(index).and_modify(|e| *e = value).or_insert(value);
-                    Ok(())
-                }
-                None => Err(IntCodeError::WriteMemoryOutOfBounds),
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+
+    fn debug_computer(source: &str) -> Computer<VecStorage, BufferInput, BufferOutput> {
+        let program = assemble(source).unwrap();
+        Computer::new(program, BufferInput::new(1), BufferOutput::new(1))
+    }
+
+    const COUNTDOWN: &str = "
+                in   [n]
+        loop:   out  [n]
+                add  [n], #-1, [n]
+                jt   [n], #loop
+                hlt
+        n:      data 0
+    ";
+
+    #[test]
+    fn step_runs_one_instruction() {
+        let mut computer = debug_computer(COUNTDOWN);
+        assert_eq!(StepResult::WaitingOnInput, computer.step().unwrap());
+        assert_eq!(0, computer.program_counter());
+
+        computer.input().queue(2);
+        assert_eq!(StepResult::Continue, computer.step().unwrap());
+        assert_eq!(2, computer.program_counter());
+        assert_eq!(2, computer.read_memory(12).unwrap());
+
+        assert_eq!(StepResult::Continue, computer.step().unwrap());
+        assert_eq!(Some(2), computer.output().pop());
+        assert_eq!(4, computer.program_counter());
+    }
+
+    #[test]
+    fn resume_stops_at_breakpoints() {
+        let mut computer = debug_computer(COUNTDOWN);
+        computer.input().queue(3);
+        computer.add_breakpoint(2);
+
+        assert_eq!(StepResult::Breakpoint(2), computer.resume().unwrap());
+        assert_eq!(vec![0; 0], computer.output().pop_all());
+
+        // Resuming from a breakpoint makes progress, and stops there again next time around
+        assert_eq!(StepResult::Breakpoint(2), computer.resume().unwrap());
+        assert_eq!(vec![3], computer.output().pop_all());
+
+        computer.remove_breakpoint(2);
+        assert_eq!(StepResult::Halt, computer.resume().unwrap());
+        assert_eq!(vec![2, 1], computer.output().pop_all());
+    }
+
+    #[test]
+    fn resume_stops_after_watched_writes() {
+        let mut computer = debug_computer(COUNTDOWN);
+        computer.input().queue(2);
+        computer.add_watchpoint(12);
+
+        assert_eq!(StepResult::Watchpoint(12), computer.resume().unwrap());
+        assert_eq!(2, computer.program_counter());
+        assert_eq!(2, computer.read_memory(12).unwrap());
+
+        assert_eq!(StepResult::Watchpoint(12), computer.resume().unwrap());
+        assert_eq!(8, computer.program_counter());
+        assert_eq!(1, computer.read_memory(12).unwrap());
+    }
+
+    #[test]
+    fn decode_cache_sees_self_modifying_writes() {
+        let source = "
+            start:  out  [value]
+                    add  #104, #0, [start]
+                    add  [count], #-1, [count]
+                    jt   [count], #start
+                    hlt
+            value:  data 7
+            count:  data 2
+        ";
+
+        for use_cache in [false, true] {
+            let mut computer = debug_computer(source);
+            if use_cache {
+                computer.enable_decode_cache();
             }
-        } else {
-            self.state.put(index, value);
-            Ok(())
+            computer.run_until_halt().unwrap();
+            assert_eq!(vec![7, 14], computer.output().pop_all());
         }
     }
+
+    #[test]
+    fn counts_executed_instructions() {
+        let mut computer = debug_computer(COUNTDOWN);
+        assert_eq!(StepResult::WaitingOnInput, computer.resume().unwrap());
+        assert_eq!(0, computer.instruction_count());
+
+        computer.input().queue(2);
+        computer.run_until_halt().unwrap();
+        assert_eq!(8, computer.instruction_count());
+    }
+
+    #[test]
+    fn budget_stops_a_spinning_program() {
+        let mut computer = debug_computer("loop: jt #1, #loop");
+        computer.set_instruction_budget(Some(100));
+        assert_eq!(StepResult::BudgetExhausted, computer.resume().unwrap());
+        assert_eq!(100, computer.instruction_count());
+        assert_eq!(StepResult::BudgetExhausted, computer.step().unwrap());
+
+        computer.set_instruction_budget(Some(5));
+        let error = computer.run_until_halt().unwrap_err();
+        assert_eq!(IntCodeErrorKind::InstructionBudgetExhausted, error.kind());
+        assert_eq!(Some(OpCode::JumpIfTrue), error.op_code());
+        assert_eq!(105, computer.instruction_count());
+        assert_eq!(Some(0), computer.instruction_budget());
+    }
+
+    #[test]
+    fn reset_starts_the_program_again() {
+        let program = assemble(COUNTDOWN).unwrap();
+        let mut computer = Computer::new(
+            PagedStorage::from(program.clone()),
+            BufferInput::new(1),
+            BufferOutput::new(3),
+        );
+        computer.input().queue(2);
+        computer.run_until_halt().unwrap();
+        computer.state().put(5000, 1).unwrap();
+
+        computer.reset(&program).unwrap();
+        assert!(!computer.has_halted());
+        assert_eq!(
+            (0, 0),
+            (computer.program_counter(), computer.instruction_count())
+        );
+        assert_eq!(0, computer.read_memory(5000).unwrap());
+        assert_eq!(1, computer.storage().pages_allocated());
+
+        computer.input().queue(1);
+        computer.run_until_halt().unwrap();
+        assert_eq!(vec![2, 1, 1], computer.output().pop_all());
+    }
+
+    #[test]
+    fn writes_past_the_program_with_paged_storage() {
+        let source = "arb #100\nadd #7, #0, [rb+5]\nhlt";
+        let mut computer = debug_computer(source);
+        let error = computer.run_until_halt().unwrap_err();
+        assert_eq!(IntCodeErrorKind::WriteMemoryOutOfBounds, error.kind());
+        assert_eq!(Some(105), error.address());
+        assert_eq!(Some(2), error.program_counter());
+
+        let program = PagedStorage::from(assemble(source).unwrap());
+        let mut computer = Computer::new(program, NoInput, BufferOutput::new(0));
+        computer.run_until_halt().unwrap();
+
+        assert_eq!(100, computer.relative_base());
+        assert_eq!(7, computer.read_memory(105).unwrap());
+        assert_eq!(1, computer.storage().pages_allocated());
+    }
+
+    #[test]
+    fn runaway_writes_stop_at_the_memory_limit() {
+        let source = "arb #2000\nadd #7, #0, [rb+0]\nhlt";
+        let mut program = PagedStorage::with_limit(1500);
+        program.load(&assemble(source).unwrap()).unwrap();
+        let mut computer = Computer::new(program, NoInput, BufferOutput::new(0));
+
+        assert_eq!(
+            IntCodeErrorKind::WriteMemoryOutOfBounds,
+            computer.run_until_halt().unwrap_err().kind()
+        );
+        assert_eq!(1, computer.storage().pages_allocated());
+    }
 }
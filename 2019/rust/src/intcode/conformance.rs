@@ -0,0 +1,536 @@
+// Tests which pin down the behaviour of the Computer, one op code and addressing mode at a time,
+// and then check random programs against a deliberately simple reference interpreter.
+use super::computer::{OpCode, ParameterTypes};
+use super::*;
+use proptest::prelude::*;
+use std::collections::VecDeque;
+
+const RELATIVE_BASE: MemoryCell = 40;
+const DATA: MemoryCell = 30;
+const OUTPUT: MemoryCell = 45;
+const MODES: [MemoryCell; 3] = [0, 1, 2];
+
+type TestComputer = Computer<PagedStorage, BufferInput, BufferOutput>;
+
+fn computer_for(program: Vec<MemoryCell>) -> TestComputer {
+    Computer::new(
+        PagedStorage::from(program),
+        BufferInput::new(4),
+        BufferOutput::new(4),
+    )
+}
+
+// Build `arb #40`, followed by the instruction under test, followed by `hlt`.
+// Inputs are read from 30 and 31 (relative offsets -10 and -9), and the output goes to 45
+// (relative offset +5), so every mode resolves to the same values.
+fn single_instruction(
+    op_code: OpCode,
+    modes: &[MemoryCell],
+    values: &[MemoryCell],
+) -> Vec<MemoryCell> {
+    let types = op_code.parameter_types();
+    let mut program = vec![0; 64];
+    program[0] = 109;
+    program[1] = RELATIVE_BASE;
+
+    let mut instruction = op_code.number();
+    for (idx, mode) in modes.iter().enumerate() {
+        instruction += mode * [100, 1000, 10000][idx];
+    }
+    program[2] = instruction;
+
+    for (idx, mode) in modes.iter().enumerate() {
+        let (address, value) = match idx < types.inputs {
+            true => (DATA + idx as MemoryCell, values[idx]),
+            false => (OUTPUT, OUTPUT),
+        };
+        program[address as usize] = value;
+        program[3 + idx] = match mode {
+            0 => address,
+            1 => value,
+            _ => address - RELATIVE_BASE,
+        };
+    }
+    program[3 + modes.len()] = 99;
+    program
+}
+
+// Every combination of modes for the given parameters, with immediate outputs left out
+fn mode_combinations(types: ParameterTypes) -> Vec<Vec<MemoryCell>> {
+    let mut combinations = vec![vec![]];
+    for idx in 0..types.total_cells() {
+        let is_output = idx == types.inputs;
+        combinations = combinations
+            .into_iter()
+            .flat_map(|modes| {
+                MODES
+                    .iter()
+                    .filter(move |m| !is_output || **m != 1)
+                    .map(move |m| {
+                        let mut modes = modes.clone();
+                        modes.push(*m);
+                        modes
+                    })
+            })
+            .collect();
+    }
+    combinations
+}
+
+fn step_past_prologue(computer: &mut TestComputer) {
+    assert_eq!(StepResult::Continue, computer.step().unwrap());
+    assert_eq!(RELATIVE_BASE, computer.relative_base());
+}
+
+#[test]
+fn every_op_code_in_every_mode() {
+    let pairs = [(7, -3), (0, 5), (4, 4), (-2, 0), (3, 12)];
+    for op_code in OpCode::ALL {
+        let types = op_code.parameter_types();
+        for modes in mode_combinations(types) {
+            for (a, b) in pairs {
+                let is_jump = matches!(op_code, OpCode::JumpIfTrue | OpCode::JumpIfFalse);
+                let b = if is_jump { 20 } else { b };
+                let program = single_instruction(op_code, &modes, &[a, b]);
+                let mut computer = computer_for(program);
+                computer.input().queue(a);
+                step_past_prologue(&mut computer);
+
+                let description = format!("{:?} {:?} with {} and {}", op_code, modes, a, b);
+                let step = computer.step().expect(&description);
+                let next = 3 + types.total_cells();
+                let stored = computer.read_memory(OUTPUT as usize).unwrap();
+
+                match op_code {
+                    OpCode::Add => assert_eq!(a + b, stored, "{}", description),
+                    OpCode::Multiply => assert_eq!(a * b, stored, "{}", description),
+                    OpCode::LessThan => {
+                        assert_eq!((a < b) as MemoryCell, stored, "{}", description)
+                    }
+                    OpCode::Equals => assert_eq!((a == b) as MemoryCell, stored, "{}", description),
+                    OpCode::Input => assert_eq!(a, stored, "{}", description),
+                    OpCode::Output => {
+                        assert_eq!(vec![a], computer.output().pop_all(), "{}", description)
+                    }
+                    OpCode::SetRelativeBase => {
+                        assert_eq!(
+                            RELATIVE_BASE + a,
+                            computer.relative_base(),
+                            "{}",
+                            description
+                        )
+                    }
+                    OpCode::JumpIfTrue | OpCode::JumpIfFalse => {
+                        let taken = (a != 0) == (op_code == OpCode::JumpIfTrue);
+                        let expected = if taken { b as usize } else { next };
+                        assert_eq!(expected, computer.program_counter(), "{}", description);
+                    }
+                    OpCode::Halt => {
+                        assert_eq!(StepResult::Halt, step);
+                        assert!(computer.has_halted());
+                    }
+                }
+
+                if !is_jump && op_code != OpCode::Halt {
+                    assert_eq!(StepResult::Continue, step, "{}", description);
+                    assert_eq!(next, computer.program_counter(), "{}", description);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn immediate_mode_outputs_are_rejected() {
+    for op_code in OpCode::ALL {
+        let types = op_code.parameter_types();
+        if !types.has_output_parameter {
+            continue;
+        }
+
+        let mut modes = vec![0; types.inputs];
+        modes.push(1);
+        let mut computer = computer_for(single_instruction(op_code, &modes, &[1, 2]));
+        computer.input().queue(1);
+        step_past_prologue(&mut computer);
+
+        let error = computer.step().unwrap_err();
+        assert_eq!(
+            IntCodeErrorKind::OutputParameterInImmediateMode,
+            error.kind()
+        );
+        assert_eq!(Some(op_code), error.op_code());
+        assert_eq!(Some(2), error.program_counter());
+    }
+}
+
+#[test]
+fn instructions_longer_than_five_digits_are_rejected() {
+    for op_code in OpCode::ALL {
+        let modes = vec![0; op_code.parameter_types().total_cells()];
+        let mut program = single_instruction(op_code, &modes, &[1, 2]);
+        program[2] += 100000;
+        let mut computer = computer_for(program);
+        computer.input().queue(1);
+        step_past_prologue(&mut computer);
+
+        let error = computer.step().unwrap_err();
+        assert_eq!(IntCodeErrorKind::UnknownParameterType, error.kind());
+        assert_eq!(Some(1), error.value());
+        assert_eq!(Some(2), error.program_counter());
+    }
+}
+
+#[test]
+fn relative_base_writes() {
+    let program = assemble(
+        "
+                arb  #100
+                add  #5, #6, [rb+3]
+                arb  #-97
+                mul  [rb+100], #2, [rb-1]
+                out  [rb-1]
+                out  [103]
+                hlt
+        ",
+    )
+    .unwrap();
+    let mut computer = computer_for(program);
+    computer.run_until_halt().unwrap();
+
+    assert_eq!(3, computer.relative_base());
+    assert_eq!(vec![22, 11], computer.output().pop_all());
+    assert_eq!(22, computer.read_memory(2).unwrap());
+}
+
+#[test]
+fn reads_and_writes_beyond_the_program() {
+    let read: Vec<MemoryCell> = vec![4, 50, 99];
+    let write: Vec<MemoryCell> = vec![1101, 1, 2, 50, 99];
+
+    // Plain vectors are exactly the size of the program
+    let mut computer = Computer::new(read.clone(), NoInput, BufferOutput::new(1));
+    assert_eq!(read.len(), computer.storage().size());
+    let error = computer.run_until_halt().unwrap_err();
+    assert_eq!(
+        IntCodeError::read_out_of_bounds(50).at_instruction(0, Some(read[0])),
+        error
+    );
+
+    let mut computer = Computer::new(write.clone(), NoInput, BufferOutput::new(1));
+    let error = computer.run_until_halt().unwrap_err();
+    assert_eq!(IntCodeErrorKind::WriteMemoryOutOfBounds, error.kind());
+    assert_eq!(Some(50), error.address());
+
+    // Paged storage has room to spare, which reads as zero until it is written
+    let mut computer = Computer::new(PagedStorage::from(read), NoInput, BufferOutput::new(1));
+    computer.run_until_halt().unwrap();
+    assert_eq!(Some(0), computer.output().last());
+
+    let mut computer = Computer::new(
+        PagedStorage::from(write.clone()),
+        NoInput,
+        BufferOutput::new(0),
+    );
+    computer.run_until_halt().unwrap();
+    assert_eq!(3, computer.read_memory(50).unwrap());
+
+    // ...up to its limit
+    let mut storage = PagedStorage::with_limit(50);
+    storage.load(&write).unwrap();
+    let mut computer = Computer::new(storage, NoInput, BufferOutput::new(0));
+    assert_eq!(
+        IntCodeErrorKind::WriteMemoryOutOfBounds,
+        computer.run_until_halt().unwrap_err().kind()
+    );
+}
+
+#[test]
+fn resumes_after_waiting_on_input() {
+    let program =
+        assemble("in [a]\nin [b]\nadd [a], [b], [a]\nout [a]\nhlt\na: data 0\nb: data 0").unwrap();
+    let mut computer = computer_for(program);
+
+    assert_eq!(StepResult::WaitingOnInput, computer.resume().unwrap());
+    assert_eq!(StepResult::WaitingOnInput, computer.step().unwrap());
+    assert_eq!(
+        (0, 0),
+        (computer.program_counter(), computer.instruction_count())
+    );
+
+    computer.input().queue(5);
+    assert_eq!(StepResult::WaitingOnInput, computer.resume().unwrap());
+    assert_eq!(
+        (2, 1),
+        (computer.program_counter(), computer.instruction_count())
+    );
+
+    computer.input().queue(8);
+    assert_eq!(StepResult::Halt, computer.resume().unwrap());
+    assert_eq!(vec![13], computer.output().pop_all());
+    assert_eq!(5, computer.instruction_count());
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Outcome {
+    Halted,
+    WaitingOnInput,
+    OutOfBudget,
+    Failed,
+}
+
+// The simplest interpreter that could possibly work, written straight from the puzzle text.
+// Memory grows on write, up to `limit` cells.
+struct Reference {
+    memory: Vec<MemoryCell>,
+    limit: usize,
+    pc: usize,
+    relative_base: MemoryCell,
+    inputs: VecDeque<MemoryCell>,
+    outputs: Vec<MemoryCell>,
+    steps: usize,
+}
+
+impl Reference {
+    fn new(program: &[MemoryCell], limit: usize) -> Reference {
+        Reference {
+            memory: program.to_vec(),
+            limit,
+            pc: 0,
+            relative_base: 0,
+            inputs: VecDeque::new(),
+            outputs: Vec::new(),
+            steps: 0,
+        }
+    }
+
+    fn read(&self, at: MemoryCell) -> Option<MemoryCell> {
+        if at < 0 || at as usize >= self.limit {
+            return None;
+        }
+        Some(
+            self.memory
+                .as_slice()
+                .get(at as usize)
+                .copied()
+                .unwrap_or(0),
+        )
+    }
+
+    fn write(&mut self, at: MemoryCell, value: MemoryCell) -> Option<()> {
+        if at < 0 || at as usize >= self.limit {
+            return None;
+        }
+        if at as usize >= self.memory.len() {
+            self.memory.resize(at as usize + 1, 0);
+        }
+        self.memory[at as usize] = value;
+        Some(())
+    }
+
+    fn address(&self, n: usize) -> Option<MemoryCell> {
+        let instruction = self.read(self.pc as MemoryCell)?;
+        let raw = self.read((self.pc + n) as MemoryCell)?;
+        match instruction / [0, 100, 1000, 10000][n] % 10 {
+            0 if raw >= 0 => Some(raw),
+            2 => self.relative_base.checked_add(raw),
+            _ => None,
+        }
+    }
+
+    fn param(&self, n: usize) -> Option<MemoryCell> {
+        let instruction = self.read(self.pc as MemoryCell)?;
+        match instruction / [0, 100, 1000, 10000][n] % 10 {
+            1 => self.read((self.pc + n) as MemoryCell),
+            _ => self.read(self.address(n)?),
+        }
+    }
+
+    fn jump(&mut self, condition: bool) -> Option<()> {
+        let target = self.param(2)?;
+        if condition {
+            if target < 0 {
+                return None;
+            }
+            self.pc = target as usize;
+        } else {
+            self.pc += 3;
+        }
+        Some(())
+    }
+
+    // Returns None if the program breaks
+    fn step(&mut self) -> Option<Outcome> {
+        let instruction = self.read(self.pc as MemoryCell)?;
+        if instruction < 0 {
+            return None;
+        }
+        let (a, b) = (|| self.param(1), || self.param(2));
+        match instruction % 100 {
+            1 => {
+                let value = a()?.checked_add(b()?)?;
+                self.write(self.address(3)?, value)?;
+                self.pc += 4;
+            }
+            2 => {
+                let value = a()?.checked_mul(b()?)?;
+                self.write(self.address(3)?, value)?;
+                self.pc += 4;
+            }
+            3 => {
+                let address = self.address(1)?;
+                match self.inputs.pop_front() {
+                    Some(value) => self.write(address, value)?,
+                    None => return Some(Outcome::WaitingOnInput),
+                }
+                self.pc += 2;
+            }
+            4 => {
+                self.outputs.push(a()?);
+                self.pc += 2;
+            }
+            5 => self.jump(a()? != 0)?,
+            6 => self.jump(a()? == 0)?,
+            7 => {
+                let value = (a()? < b()?) as MemoryCell;
+                self.write(self.address(3)?, value)?;
+                self.pc += 4;
+            }
+            8 => {
+                let value = (a()? == b()?) as MemoryCell;
+                self.write(self.address(3)?, value)?;
+                self.pc += 4;
+            }
+            9 => {
+                self.relative_base = self.relative_base.checked_add(a()?)?;
+                self.pc += 2;
+            }
+            99 => {
+                self.steps += 1;
+                return Some(Outcome::Halted);
+            }
+            _ => return None,
+        }
+        self.steps += 1;
+        Some(Outcome::OutOfBudget)
+    }
+
+    fn run(&mut self, budget: usize) -> Outcome {
+        while self.steps < budget {
+            match self.step() {
+                Some(Outcome::OutOfBudget) => (),
+                Some(outcome) => return outcome,
+                None => return Outcome::Failed,
+            }
+        }
+        Outcome::OutOfBudget
+    }
+}
+
+fn outcome_of(result: IntCodeResult<StepResult>) -> Outcome {
+    match result {
+        Ok(StepResult::Halt) => Outcome::Halted,
+        Ok(StepResult::WaitingOnInput) => Outcome::WaitingOnInput,
+        Ok(StepResult::BudgetExhausted) => Outcome::OutOfBudget,
+        Ok(other) => panic!("Unexpected step result {:?}", other),
+        Err(_) => Outcome::Failed,
+    }
+}
+
+const BUDGET: usize = 200;
+
+// Run a program on both the Computer and the reference, half of the input at a time,
+// and check that they agree at every point where they stop.
+fn compare<S: Storage>(program: &[MemoryCell], storage: S, limit: usize, inputs: &[MemoryCell]) {
+    let (first, second) = inputs.split_at(inputs.len() / 2);
+
+    let mut computer = Computer::new(storage, BufferInput::new(0), BufferOutput::new(0));
+    computer.set_instruction_budget(Some(BUDGET));
+    let mut reference = Reference::new(program, limit);
+
+    for batch in [first, second] {
+        computer.input().queue_many(batch);
+        reference.inputs.extend(batch);
+
+        let outcome = reference.run(BUDGET);
+        assert_eq!(outcome, outcome_of(computer.resume()));
+        assert_eq!(reference.outputs, computer.output().pop_all());
+        reference.outputs.clear();
+        assert_eq!(reference.steps, computer.instruction_count());
+
+        if outcome == Outcome::Failed {
+            return;
+        }
+        assert_eq!(reference.pc, computer.program_counter());
+        assert_eq!(reference.relative_base, computer.relative_base());
+        for (idx, value) in reference.memory.iter().enumerate() {
+            assert_eq!(Ok(*value), computer.read_memory(idx), "memory at {}", idx);
+        }
+        if outcome != Outcome::WaitingOnInput {
+            return;
+        }
+    }
+}
+
+// Random but mostly well formed instructions, so that programs run for a while before breaking
+fn instruction() -> impl Strategy<Value = Vec<MemoryCell>> {
+    (
+        prop::sample::select(OpCode::ALL.to_vec()),
+        prop::array::uniform3(prop::sample::select(MODES.to_vec())),
+        prop::array::uniform3(prop_oneof![4 => 0..48 as MemoryCell, 1 => -50..50 as MemoryCell]),
+    )
+        .prop_map(|(op_code, modes, params)| {
+            let types = op_code.parameter_types();
+            let cells = types.total_cells();
+            let mut instruction = op_code.number();
+            for (idx, mode) in modes.iter().enumerate().take(cells) {
+                // Immediate outputs are tested above, they'd stop most programs straight away
+                let mode = if idx == types.inputs && *mode == 1 {
+                    0
+                } else {
+                    *mode
+                };
+                instruction += mode * [100, 1000, 10000][idx];
+            }
+            let mut encoded = vec![instruction];
+            encoded.extend(&params[..cells]);
+            encoded
+        })
+}
+
+// Finish with a halt, so that programs which run straight through don't break on a zero
+fn program() -> impl Strategy<Value = Vec<MemoryCell>> {
+    prop::collection::vec(instruction(), 1..16).prop_map(|instructions| {
+        let mut program = instructions.concat();
+        program.push(99);
+        program
+    })
+}
+
+proptest! {
+    #[test]
+    fn agrees_with_the_reference_on_paged_storage(
+        program in program(),
+        inputs in prop::collection::vec(-100..100 as MemoryCell, 0..6),
+    ) {
+        let limit = 128;
+        let mut storage = PagedStorage::with_limit(limit.max(program.len()));
+        storage.load(&program).unwrap();
+        compare(&program, storage, limit.max(program.len()), &inputs);
+    }
+
+    #[test]
+    fn agrees_with_the_reference_on_fixed_storage(
+        program in program(),
+        inputs in prop::collection::vec(-100..100 as MemoryCell, 0..6),
+    ) {
+        compare(&program, program.clone(), program.len(), &inputs);
+    }
+
+    #[test]
+    fn decoding_matches_the_formatted_decoder(instruction in instruction()) {
+        let arithmetic = decode(&instruction, 0);
+        let formatted = decode_formatted(&instruction, 0);
+        prop_assert_eq!(arithmetic.ok(), formatted.ok());
+    }
+}
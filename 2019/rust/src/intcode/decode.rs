@@ -0,0 +1,200 @@
+use super::computer::{cast_cell_to_pointer, OpCode, Operation, Parameter};
+use super::{
+    IntCodeCell, IntCodeError, IntCodeErrorKind, IntCodeResult, MemoryCell, MemoryPointer,
+};
+
+// Decode the operation at the given address, reading memory through `read`.
+// The op code and parameter modes are pulled out of the instruction arithmetically.
+pub(super) fn decode_with<C, F>(read: F, from: MemoryPointer) -> IntCodeResult<Operation<C>>
+where
+    C: IntCodeCell,
+    F: Fn(MemoryPointer) -> IntCodeResult<C>,
+{
+    let raw = read(from)?;
+    let unknown = || IntCodeError::new(IntCodeErrorKind::UnknownOpCode).with_value(raw);
+    let op = raw.to_i64().filter(|op| *op >= 0).ok_or_else(unknown)?;
+    let op_code = OpCode::from_number(op % 100).map_err(|_| unknown())?;
+    if op >= 100000 {
+        // No instruction has a fourth parameter, so there's no mode for a sixth digit to set
+        let mode = op / 100000;
+        return Err(IntCodeError::new(IntCodeErrorKind::UnknownParameterType).with_value(mode));
+    }
+    let parameter_types = op_code.parameter_types();
+
+    // Build a parameter by determining its type and reading its location/value
+    let build_parameter = |idx: usize| {
+        let mode = (op / [100, 1000, 10000][idx]) % 10;
+        let value = read(from + 1 + idx)?;
+        match mode {
+            0 => Ok(Parameter::Position(cast_cell_to_pointer(value)?)),
+            1 => Ok(Parameter::Immediate(value)),
+            2 => Ok(Parameter::Relative(value)),
+            _ => Err(IntCodeError::new(IntCodeErrorKind::UnknownParameterType).with_value(mode)),
+        }
+    };
+
+    let mut inputs = [Parameter::Immediate(C::zero()); 2];
+    for (idx, input) in inputs.iter_mut().enumerate().take(parameter_types.inputs) {
+        *input = build_parameter(idx)?;
+    }
+
+    let output = if parameter_types.has_output_parameter {
+        // If we have an output parameter, find it immediately after the inputs
+        let param = build_parameter(parameter_types.inputs)?;
+        if let Parameter::Immediate(_) = param {
+            return Err(IntCodeErrorKind::OutputParameterInImmediateMode.into());
+        }
+        Some(param)
+    } else {
+        None
+    };
+
+    Ok(Operation {
+        op_code,
+        parameter_types,
+        inputs,
+        output,
+    })
+}
+
+// The original decoder, which formats the instruction as a string to find its digits.
+// It's kept around so that the benchmarks can compare against it.
+fn decode_formatted_with<F>(read: F, from: MemoryPointer) -> IntCodeResult<Operation>
+where
+    F: Fn(MemoryPointer) -> IntCodeResult<MemoryCell>,
+{
+    let op = read(from)?;
+    let digits = format!("{:05}", op);
+    assert_eq!(5, digits.len());
+
+    let op_code = OpCode::from_digits(&digits[3..5])?;
+    let parameter_types = op_code.parameter_types();
+
+    // Make a positional parameter by reading a memory location from the given index
+    let make_positional = |idx: usize| {
+        let location: MemoryPointer = cast_cell_to_pointer(read(from + 1 + idx)?)?;
+        Ok(Parameter::Position(location))
+    };
+
+    // Make an immediate parameter by reading a value from the given index
+    let make_immediate = |idx: usize| {
+        let value = read(from + 1 + idx)?;
+        Ok(Parameter::Immediate(value))
+    };
+
+    // Make a relative parameter by reading a memory offset from the given index
+    let make_relative = |idx: usize| {
+        let offset: MemoryCell = read(from + 1 + idx)?;
+        Ok(Parameter::Relative(offset))
+    };
+
+    // Build a parameter by determining its type and reading its location/value
+    let build_parameter = |idx: usize| {
+        if idx > parameter_types.inputs {
+            return Err(IntCodeErrorKind::InvalidParameterIndex.into());
+        }
+
+        let mode_digit = 2 - idx;
+        let mode = &digits[mode_digit..=mode_digit];
+        match mode {
+            "0" => make_positional(idx),
+            "1" => make_immediate(idx),
+            "2" => make_relative(idx),
+            _ => Err(IntCodeErrorKind::UnknownParameterType.into()),
+        }
+    };
+
+    let parameters = (0..parameter_types.inputs)
+        .map(build_parameter)
+        .collect::<IntCodeResult<Vec<_>>>()?;
+
+    let output = if parameter_types.has_output_parameter {
+        // If we have an output parameter, find it immediately after the inputs
+        let param = build_parameter(parameter_types.inputs)?;
+        if let Parameter::Immediate(_) = param {
+            return Err(IntCodeErrorKind::OutputParameterInImmediateMode.into());
+        }
+        Some(param)
+    } else {
+        None
+    };
+
+    let mut inputs = [Parameter::Immediate(0); 2];
+    inputs[..parameters.len()].copy_from_slice(&parameters);
+
+    Ok(Operation {
+        op_code,
+        parameter_types,
+        inputs,
+        output,
+    })
+}
+
+fn read_slice(program: &[MemoryCell]) -> impl Fn(MemoryPointer) -> IntCodeResult<MemoryCell> + '_ {
+    move |at| {
+        program
+            .get(at)
+            .copied()
+            .ok_or_else(|| IntCodeError::read_out_of_bounds(at))
+    }
+}
+
+pub fn decode(program: &[MemoryCell], at: MemoryPointer) -> IntCodeResult<Operation> {
+    decode_with(read_slice(program), at).map_err(|e| e.at_instruction(at, program.get(at).copied()))
+}
+
+pub fn decode_formatted(program: &[MemoryCell], at: MemoryPointer) -> IntCodeResult<Operation> {
+    decode_formatted_with(read_slice(program), at)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::load_program;
+    use super::*;
+
+    #[test]
+    fn decodes_modes_arithmetically() {
+        let op = decode(&[21002, 4, 3, 4], 0).unwrap();
+        assert_eq!(OpCode::Multiply, op.op_code());
+        assert_eq!(
+            &[Parameter::Position(4), Parameter::Immediate(3)],
+            op.inputs()
+        );
+        assert_eq!(Some(&Parameter::Relative(4)), op.output());
+    }
+
+    #[test]
+    fn rejects_invalid_instructions() {
+        let kind = |program: &[MemoryCell]| decode(program, 0).unwrap_err().kind();
+        assert_eq!(IntCodeErrorKind::UnknownOpCode, kind(&[-1]));
+        assert_eq!(IntCodeErrorKind::UnknownOpCode, kind(&[42]));
+        assert_eq!(IntCodeErrorKind::UnknownParameterType, kind(&[304, 0]));
+        assert_eq!(IntCodeErrorKind::UnknownParameterType, kind(&[100099]));
+        assert_eq!(
+            IntCodeErrorKind::OutputParameterInImmediateMode,
+            kind(&[11101, 1, 1, 1])
+        );
+        assert_eq!(IntCodeErrorKind::ReadMemoryOutOfBounds, kind(&[1, 0, 0]));
+
+        let error = decode(&[0, 42], 1).unwrap_err();
+        assert_eq!(Some(42), error.value());
+        assert_eq!(Some(1), error.program_counter());
+        assert_eq!(None, error.op_code());
+    }
+
+    #[test]
+    fn agrees_with_formatted_decoder_on_puzzle_inputs() {
+        for day in [2, 5, 7, 9, 11, 13, 15, 17] {
+            let program = load_program(&format!("inputs/day{}.txt", day));
+            for at in 0..program.len() {
+                // The formatted decoder can't cope with negative or oversized cells
+                if !(0..100000).contains(&program[at]) {
+                    continue;
+                }
+                let arithmetic = decode(&program, at).ok();
+                let formatted = decode_formatted(&program, at).ok();
+                assert_eq!(formatted, arithmetic, "Day {} address {}", day, at);
+            }
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use super::computer::{OpCode, Parameter};
+use super::{IntCodeCell, MemoryCell, MemoryPointer};
+use std::convert::TryInto;
+use std::fmt;
+
+// How many undecodable cells are grouped on to a single data line
+const DATA_CELLS_PER_LINE: usize = 8;
+
+pub struct Listing {
+    lines: Vec<ListingLine>,
+}
+
+struct ListingLine {
+    address: MemoryPointer,
+    raw: Vec<MemoryCell>,
+    content: LineContent,
+}
+
+enum LineContent {
+    Instruction(OpCode, Vec<Parameter>),
+    Data,
+}
+
+impl<C: IntCodeCell> fmt::Display for Parameter<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Parameter::Position(addr) => write!(f, "[{}]", addr),
+            Parameter::Immediate(value) => write!(f, "#{}", value),
+            Parameter::Relative(offset) if *offset < C::zero() => write!(f, "[rb{}]", offset),
+            Parameter::Relative(offset) => write!(f, "[rb+{}]", offset),
+        }
+    }
+}
+
+// Try to decode the cell at the given address as an instruction.
+// Anything the Computer would refuse to run, or that would not re-encode to
+// exactly the same cells, is treated as data instead.
+fn decode_at(program: &[MemoryCell], at: MemoryPointer) -> Option<(OpCode, Vec<Parameter>)> {
+    let raw = program[at];
+    if !(0..100000).contains(&raw) {
+        return None;
+    }
+
+    let digits = format!("{:05}", raw);
+    let op_code = OpCode::from_digits(&digits[3..5]).ok()?;
+    let parameter_types = op_code.parameter_types();
+    let total = parameter_types.total_cells();
+
+    // Mode digits for parameters the op doesn't have must be zero
+    if (total..3).any(|idx| &digits[(2 - idx)..=(2 - idx)] != "0") {
+        return None;
+    }
+
+    let parameters = (0..total)
+        .map(|idx| {
+            let value = *program.get(at + 1 + idx)?;
+            let mode_digit = 2 - idx;
+            match &digits[mode_digit..=mode_digit] {
+                "0" => Some(Parameter::Position(value.try_into().ok()?)),
+                "1" => Some(Parameter::Immediate(value)),
+                "2" => Some(Parameter::Relative(value)),
+                _ => None,
+            }
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    if parameter_types.has_output_parameter {
+        if let Some(Parameter::Immediate(_)) = parameters.last() {
+            return None;
+        }
+    }
+
+    Some((op_code, parameters))
+}
+
+pub fn disassemble(program: &[MemoryCell]) -> Listing {
+    disassemble_from(program, 0, usize::MAX)
+}
+
+// Disassemble at most `max_lines` lines, starting from the given address
+pub fn disassemble_from(program: &[MemoryCell], from: MemoryPointer, max_lines: usize) -> Listing {
+    let mut lines: Vec<ListingLine> = Vec::new();
+    let mut at = from;
+
+    while at < program.len() && lines.len() <= max_lines {
+        match decode_at(program, at) {
+            Some((op_code, parameters)) => {
+                let length = 1 + parameters.len();
+                lines.push(ListingLine {
+                    address: at,
+                    raw: Vec::from(&program[at..(at + length)]),
+                    content: LineContent::Instruction(op_code, parameters),
+                });
+                at += length;
+            }
+            None => {
+                // Extend the previous data line if there is room, otherwise start a new one
+                match lines.last_mut() {
+                    Some(ListingLine {
+                        raw,
+                        content: LineContent::Data,
+                        ..
+                    }) if raw.len() < DATA_CELLS_PER_LINE => raw.push(program[at]),
+                    _ => lines.push(ListingLine {
+                        address: at,
+                        raw: vec![program[at]],
+                        content: LineContent::Data,
+                    }),
+                }
+                at += 1;
+            }
+        }
+    }
+
+    lines.truncate(max_lines);
+    Listing { lines }
+}
+
+fn join<T: fmt::Display>(values: &[T]) -> String {
+    values
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl fmt::Display for ListingLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match &self.content {
+            LineContent::Instruction(op_code, parameters) => {
+                format!("{:<4} {}", op_code.mnemonic(), join(parameters))
+            }
+            LineContent::Data => format!("{:<4} {}", "data", join(&self.raw)),
+        };
+        let text = text.trim_end();
+
+        write!(f, "{:>6}: {}", self.address, text)?;
+
+        // Annotate instructions with the raw cells they were decoded from
+        if let LineContent::Instruction(_, _) = self.content {
+            let raw = self
+                .raw
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            write!(
+                f,
+                "{:pad$}; {}",
+                "",
+                raw,
+                pad = 32usize.saturating_sub(text.len())
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Listing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in &self.lines {
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listing_lines(program: &[MemoryCell]) -> Vec<String> {
+        disassemble(program)
+            .to_string()
+            .lines()
+            .map(|l| l.split(';').next().unwrap().trim().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn renders_each_addressing_mode() {
+        assert_eq!(
+            vec!["0: mul  [4], #3, [4]", "4: data 33"],
+            listing_lines(&[1002, 4, 3, 4, 33])
+        );
+        assert_eq!(
+            vec!["0: arb  #1", "2: out  [rb-1]", "4: hlt"],
+            listing_lines(&[109, 1, 204, -1, 99])
+        );
+    }
+
+    #[test]
+    fn marks_undecodable_cells_as_data() {
+        assert_eq!(
+            vec![
+                "0: in   [9]",
+                "2: data 98, -4, 11101, 100",
+                "6: jt   [0], #3"
+            ],
+            listing_lines(&[3, 9, 98, -4, 11101, 100, 1005, 0, 3])
+        );
+    }
+
+    #[test]
+    fn truncated_instruction_is_data() {
+        assert_eq!(vec!["0: data 1, 0, 0"], listing_lines(&[1, 0, 0]));
+    }
+
+    #[test]
+    fn disassembles_puzzle_inputs() {
+        for day in [2, 5, 7, 9, 11, 13, 15, 17] {
+            let program = crate::util::read_int_array(&format!("inputs/day{}.txt", day));
+            let cells: usize = disassemble(&program)
+                .lines
+                .iter()
+                .map(|l| l.raw.len())
+                .sum();
+            assert_eq!(program.len(), cells);
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use super::computer::OpCode;
+use super::{IntCodeCell, MemoryPointer};
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum IntCodeErrorKind {
+    UnknownOpCode,
+    ReadMemoryOutOfBounds,
+    WriteMemoryOutOfBounds,
+    InvalidParameterIndex,
+    UnknownParameterType,
+    OutputParameterInImmediateMode,
+    MemoryCellIsInvalidPointer,
+    EffectMismatch,
+    SnapshotSizeMismatch,
+    InstructionBudgetExhausted,
+    ArithmeticOverflow,
+    NoOutput,
+}
+
+impl IntCodeErrorKind {
+    fn description(&self) -> &'static str {
+        match self {
+            IntCodeErrorKind::UnknownOpCode => "unknown op code",
+            IntCodeErrorKind::ReadMemoryOutOfBounds => "read outside of memory",
+            IntCodeErrorKind::WriteMemoryOutOfBounds => "write outside of memory",
+            IntCodeErrorKind::InvalidParameterIndex => "invalid parameter index",
+            IntCodeErrorKind::UnknownParameterType => "unknown parameter mode",
+            IntCodeErrorKind::OutputParameterInImmediateMode => {
+                "output parameter in immediate mode"
+            }
+            IntCodeErrorKind::MemoryCellIsInvalidPointer => "memory cell is not a valid pointer",
+            IntCodeErrorKind::EffectMismatch => "operation had an unexpected effect",
+            IntCodeErrorKind::SnapshotSizeMismatch => "snapshot doesn't fit in memory",
+            IntCodeErrorKind::InstructionBudgetExhausted => "instruction budget exhausted",
+            IntCodeErrorKind::ArithmeticOverflow => "arithmetic overflow",
+            IntCodeErrorKind::NoOutput => "program produced no output",
+        }
+    }
+}
+
+// What went wrong, and as much as is known about where it happened.
+// Errors start out with just a kind and the offending address or value,
+// and the Computer fills in the instruction it was executing.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct IntCodeError {
+    kind: IntCodeErrorKind,
+    program_counter: Option<MemoryPointer>,
+    instruction: Option<i128>,
+    op_code: Option<OpCode>,
+    address: Option<MemoryPointer>,
+    value: Option<i128>,
+}
+
+impl IntCodeError {
+    pub fn new(kind: IntCodeErrorKind) -> IntCodeError {
+        IntCodeError {
+            kind,
+            program_counter: None,
+            instruction: None,
+            op_code: None,
+            address: None,
+            value: None,
+        }
+    }
+
+    pub fn read_out_of_bounds(address: MemoryPointer) -> IntCodeError {
+        IntCodeError::new(IntCodeErrorKind::ReadMemoryOutOfBounds).with_address(address)
+    }
+
+    pub fn write_out_of_bounds(address: MemoryPointer) -> IntCodeError {
+        IntCodeError::new(IntCodeErrorKind::WriteMemoryOutOfBounds).with_address(address)
+    }
+
+    pub fn with_address(mut self, address: MemoryPointer) -> IntCodeError {
+        self.address = Some(address);
+        self
+    }
+
+    pub fn with_value<C: IntCodeCell>(mut self, value: C) -> IntCodeError {
+        self.value = Some(value.to_i128());
+        self
+    }
+
+    // Record the instruction which was executing. The first location recorded wins,
+    // so this can be applied at every level without losing the innermost one.
+    pub fn at_instruction<C: IntCodeCell>(
+        mut self,
+        program_counter: MemoryPointer,
+        instruction: Option<C>,
+    ) -> IntCodeError {
+        if self.program_counter.is_some() {
+            return self;
+        }
+
+        self.program_counter = Some(program_counter);
+        self.instruction = instruction.map(|i| i.to_i128());
+        self.op_code = instruction
+            .and_then(|i| i.to_i64())
+            .filter(|i| *i >= 0)
+            .and_then(|i| OpCode::from_number(i % 100).ok());
+        self
+    }
+
+    pub fn kind(&self) -> IntCodeErrorKind {
+        self.kind
+    }
+
+    // The address of the instruction which was executing
+    pub fn program_counter(&self) -> Option<MemoryPointer> {
+        self.program_counter
+    }
+
+    // The raw value of the instruction, including its parameter modes
+    pub fn instruction(&self) -> Option<i128> {
+        self.instruction
+    }
+
+    pub fn op_code(&self) -> Option<OpCode> {
+        self.op_code
+    }
+
+    // The memory address which couldn't be read or written
+    pub fn address(&self) -> Option<MemoryPointer> {
+        self.address
+    }
+
+    // The value which couldn't be used, such as a negative pointer or a bad op code
+    pub fn value(&self) -> Option<i128> {
+        self.value
+    }
+}
+
+impl From<IntCodeErrorKind> for IntCodeError {
+    fn from(kind: IntCodeErrorKind) -> Self {
+        IntCodeError::new(kind)
+    }
+}
+
+impl fmt::Display for IntCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.kind.description())?;
+        if let Some(address) = self.address {
+            write!(f, " at address {}", address)?;
+        }
+        if let Some(value) = self.value {
+            write!(f, " with value {}", value)?;
+        }
+        if let Some(program_counter) = self.program_counter {
+            write!(f, ", executing the instruction at {}", program_counter)?;
+            match (self.instruction, self.op_code) {
+                (Some(instruction), Some(op_code)) => {
+                    write!(f, " ({}, {})", instruction, op_code.mnemonic())?
+                }
+                (Some(instruction), None) => write!(f, " ({})", instruction)?,
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for IntCodeError {}
+
+pub type IntCodeResult<T> = Result<T, IntCodeError>;
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+
+    #[test]
+    fn errors_say_where_the_program_broke() {
+        // Jump to the address held in a cell, which is negative
+        let program = assemble("jt #1, [target]\nhlt\ntarget: data -7").unwrap();
+        let error = Computer::new(program, NoInput, BufferOutput::new(0))
+            .run_until_halt()
+            .unwrap_err();
+
+        assert_eq!(IntCodeErrorKind::MemoryCellIsInvalidPointer, error.kind());
+        assert_eq!(Some(0), error.program_counter());
+        assert_eq!(Some(105), error.instruction());
+        assert_eq!(Some(OpCode::JumpIfTrue), error.op_code());
+        assert_eq!(Some(-7), error.value());
+        assert_eq!(
+            "memory cell is not a valid pointer with value -7, executing the instruction at 0 (105, jt)",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn errors_pass_through_the_helpers() {
+        let error =
+            run_basic_intcode_program(vec![1i64, 0, 0, 0, 1, 0, 0, 1000, 99], 0).unwrap_err();
+        assert_eq!(IntCodeErrorKind::WriteMemoryOutOfBounds, error.kind());
+        assert_eq!(Some(1000), error.address());
+        assert_eq!(Some(4), error.program_counter());
+        assert_eq!(
+            "write outside of memory at address 1000, executing the instruction at 4 (1, add)",
+            error.to_string()
+        );
+
+        let error = run_io_intcode_program(vec![3i64, 0, 99], &[5]).unwrap_err();
+        assert_eq!(IntCodeErrorKind::NoOutput, error.kind());
+        assert_eq!("program produced no output", error.to_string());
+
+        let error = decode(&[1, 0], 0).unwrap_err();
+        assert_eq!(Some(2), error.address());
+        assert_eq!(Some(OpCode::Add), error.op_code());
+    }
+}
@@ -1,71 +1,71 @@
-use super::MemoryCell;
+use super::{IntCodeCell, MemoryCell};
 use std::collections::VecDeque;
 
-pub trait InputSource {
-    fn next(&mut self) -> Option<MemoryCell>;
+pub trait InputSource<C: IntCodeCell = MemoryCell> {
+    fn next(&mut self) -> Option<C>;
 }
 
-pub trait OutputSink {
-    fn write(&mut self, value: MemoryCell);
+pub trait OutputSink<C: IntCodeCell = MemoryCell> {
+    fn write(&mut self, value: C);
 }
 
-pub struct BufferInput {
-    buf: VecDeque<MemoryCell>,
+pub struct BufferInput<C: IntCodeCell = MemoryCell> {
+    buf: VecDeque<C>,
 }
 
 pub struct NoInput;
 
-pub struct BufferOutput {
-    values: VecDeque<MemoryCell>,
+pub struct BufferOutput<C: IntCodeCell = MemoryCell> {
+    values: VecDeque<C>,
 }
 
-impl BufferInput {
-    pub fn new(capacity: usize) -> BufferInput {
+impl<C: IntCodeCell> BufferInput<C> {
+    pub fn new(capacity: usize) -> BufferInput<C> {
         BufferInput {
             buf: VecDeque::with_capacity(capacity),
         }
     }
 
-    pub fn queue(&mut self, value: MemoryCell) {
+    pub fn queue(&mut self, value: C) {
         self.buf.push_back(value);
     }
 
-    pub fn queue_many(&mut self, values: &[MemoryCell]) {
+    pub fn queue_many(&mut self, values: &[C]) {
         for v in values {
             self.queue(*v);
         }
     }
 }
 
-impl InputSource for BufferInput {
-    fn next(&mut self) -> Option<MemoryCell> {
+impl<C: IntCodeCell> InputSource<C> for BufferInput<C> {
+    fn next(&mut self) -> Option<C> {
         self.buf.pop_front()
     }
 }
 
-impl InputSource for NoInput {
-    fn next(&mut self) -> Option<MemoryCell> {
+impl<C: IntCodeCell> InputSource<C> for NoInput {
+    fn next(&mut self) -> Option<C> {
         None
     }
 }
 
-impl BufferOutput {
-    pub fn new(capacity: usize) -> BufferOutput {
+impl<C: IntCodeCell> BufferOutput<C> {
+    pub fn new(capacity: usize) -> BufferOutput<C> {
         BufferOutput {
             values: VecDeque::with_capacity(capacity),
         }
     }
 
-    pub fn pop(&mut self) -> Option<MemoryCell> {
+    pub fn pop(&mut self) -> Option<C> {
         self.values.pop_front()
     }
 
-    pub fn last(&self) -> Option<MemoryCell> {
+    pub fn last(&self) -> Option<C> {
         Some(*(self.values.iter().last()?))
     }
 
-    pub fn pop_all(&mut self) -> Vec<MemoryCell> {
-        let mut result: Vec<MemoryCell> = Vec::with_capacity(self.values.len());
+    pub fn pop_all(&mut self) -> Vec<C> {
+        let mut result: Vec<C> = Vec::with_capacity(self.values.len());
         while let Some(x) = self.pop() {
             result.push(x);
         }
@@ -73,8 +73,130 @@ impl BufferOutput {
     }
 }
 
-impl OutputSink for BufferOutput {
-    fn write(&mut self, value: MemoryCell) {
+impl<C: IntCodeCell> OutputSink<C> for BufferOutput<C> {
+    fn write(&mut self, value: C) {
         self.values.push_back(value);
     }
 }
+
+// Never leaves the program waiting. When there's nothing queued it reads -1 instead,
+// and keeps count of how many times that has happened since the last real value.
+pub struct NonBlockingInput<C: IntCodeCell = MemoryCell> {
+    buf: VecDeque<C>,
+    idle_reads: usize,
+}
+
+impl<C: IntCodeCell> NonBlockingInput<C> {
+    pub fn new() -> NonBlockingInput<C> {
+        NonBlockingInput {
+            buf: VecDeque::new(),
+            idle_reads: 0,
+        }
+    }
+
+    pub fn queue(&mut self, value: C) {
+        self.buf.push_back(value);
+    }
+
+    pub fn queue_many(&mut self, values: &[C]) {
+        for v in values {
+            self.queue(*v);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    pub fn idle_reads(&self) -> usize {
+        self.idle_reads
+    }
+}
+
+impl<C: IntCodeCell> Default for NonBlockingInput<C> {
+    fn default() -> Self {
+        NonBlockingInput::new()
+    }
+}
+
+impl<C: IntCodeCell> InputSource<C> for NonBlockingInput<C> {
+    fn next(&mut self) -> Option<C> {
+        match self.buf.pop_front() {
+            Some(value) => {
+                self.idle_reads = 0;
+                Some(value)
+            }
+            None => {
+                self.idle_reads += 1;
+                C::from_i64(-1)
+            }
+        }
+    }
+}
+
+// Collects the output in to packets with a fixed number of values each
+pub struct PacketOutput<C: IntCodeCell = MemoryCell> {
+    size: usize,
+    partial: Vec<C>,
+    packets: VecDeque<Vec<C>>,
+}
+
+impl<C: IntCodeCell> PacketOutput<C> {
+    pub fn new(size: usize) -> PacketOutput<C> {
+        assert!(size > 0, "Packets need at least one value");
+        PacketOutput {
+            size,
+            partial: Vec::with_capacity(size),
+            packets: VecDeque::new(),
+        }
+    }
+
+    // The next packet which has been completely written
+    pub fn pop(&mut self) -> Option<Vec<C>> {
+        self.packets.pop_front()
+    }
+
+    pub fn pop_all(&mut self) -> Vec<Vec<C>> {
+        self.packets.drain(..).collect()
+    }
+}
+
+impl<C: IntCodeCell> OutputSink<C> for PacketOutput<C> {
+    fn write(&mut self, value: C) {
+        self.partial.push(value);
+        if self.partial.len() == self.size {
+            let packet = std::mem::replace(&mut self.partial, Vec::with_capacity(self.size));
+            self.packets.push_back(packet);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn non_blocking_input_never_waits() {
+        let mut input: NonBlockingInput = NonBlockingInput::new();
+        assert_eq!(Some(-1), input.next());
+        assert_eq!(Some(-1), input.next());
+        assert_eq!(2, input.idle_reads());
+
+        input.queue_many(&[7, 8]);
+        assert_eq!(Some(7), input.next());
+        assert_eq!(0, input.idle_reads());
+        assert_eq!(Some(8), input.next());
+        assert!(input.is_empty());
+    }
+
+    #[test]
+    fn packet_output_waits_for_whole_packets() {
+        let mut output: PacketOutput = PacketOutput::new(3);
+        output.write(255);
+        output.write(7);
+        assert_eq!(None, output.pop());
+        output.write(9);
+        output.write(1);
+        assert_eq!(vec![vec![255, 7, 9]], output.pop_all());
+    }
+}
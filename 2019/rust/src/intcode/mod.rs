@@ -1,44 +1,74 @@
+mod analysis;
+mod ascii;
+mod assembler;
+mod cell;
 mod computer;
+#[cfg(test)]
+mod conformance;
+mod decode;
+mod disassembler;
+mod error;
 mod io;
+mod network;
+mod recorder;
+mod snapshot;
 mod storage;
+mod tracer;
 
+pub use analysis::*;
+pub use ascii::*;
+pub use assembler::*;
+pub use cell::*;
 pub use computer::*;
+pub use decode::{decode, decode_formatted};
+pub use disassembler::*;
+pub use error::*;
 pub use io::*;
+pub use network::*;
+pub use recorder::*;
+pub use snapshot::*;
 pub use storage::*;
+pub use tracer::*;
 
-pub type MemoryCell = isize;
+// The cell type used by the puzzles, any IntCodeCell can be used by the Computer
+pub type MemoryCell = i64;
 pub type MemoryPointer = usize;
 
-#[derive(Debug)]
-pub enum IntCodeError {
-    UnknownOpCode,
-    ReadMemoryOutOfBounds,
-    WriteMemoryOutOfBounds,
-    InvalidParameterIndex,
-    UnknownParameterType,
-    OutputParameterInImmediateMode,
-    MemoryCellIsInvalidPointer,
-    EffectMismatch,
+pub fn load_program(path: &str) -> VecStorage {
+    crate::util::read_int_array(path)
 }
 
-pub type IntCodeResult<T> = Result<T, IntCodeError>;
+pub fn run_basic_intcode_program<C: IntCodeCell, S: Storage<C>>(
+    state: S,
+    final_addr: MemoryPointer,
+) -> IntCodeResult<C> {
+    let mut computer = Computer::new(state, NoInput, BufferOutput::new(0));
+    computer.run_until_halt()?;
+    computer.storage().get(final_addr)
+}
 
-pub fn run_basic_intcode_program<S: Storage>(
+// As above, but give up if the program hasn't halted within the budget
+pub fn run_budgeted_intcode_program<C: IntCodeCell, S: Storage<C>>(
     state: S,
     final_addr: MemoryPointer,
-) -> IntCodeResult<MemoryCell> {
+    budget: usize,
+) -> IntCodeResult<C> {
     let mut computer = Computer::new(state, NoInput, BufferOutput::new(0));
+    computer.set_instruction_budget(Some(budget));
     computer.run_until_halt()?;
-    Ok(computer.state().get(final_addr))
+    computer.storage().get(final_addr)
 }
 
-pub fn run_io_intcode_program<S: Storage>(
+pub fn run_io_intcode_program<C: IntCodeCell, S: Storage<C>>(
     state: S,
-    inputs: &[MemoryCell],
-) -> IntCodeResult<MemoryCell> {
+    inputs: &[C],
+) -> IntCodeResult<C> {
     let mut computer = Computer::new(state, BufferInput::new(inputs.len()), BufferOutput::new(1));
     computer.input().queue_many(inputs);
 
     computer.run_until_halt()?;
-    Ok(computer.output().last().expect("No output produced"))
+    computer
+        .output()
+        .last()
+        .ok_or_else(|| IntCodeErrorKind::NoOutput.into())
 }
@@ -0,0 +1,714 @@
+use super::computer::{Computer, StepResult};
+use super::io::{InputSource, OutputSink, PacketOutput};
+use super::storage::PagedStorage;
+use super::{IntCodeResult, MemoryCell};
+use std::collections::VecDeque;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+
+// A set of named Intcode computers, each running on its own thread.
+// Every value a node outputs is sent to each of the nodes it is connected to,
+// unless the network is routing packets by address instead.
+pub struct Network {
+    names: Vec<String>,
+    programs: Vec<Vec<MemoryCell>>,
+    edges: Vec<Vec<usize>>,
+    initial_inputs: Vec<Vec<MemoryCell>>,
+    packet_size: Option<usize>,
+}
+
+// Looks after the addresses outside of a packet routing network
+pub trait Gateway {
+    // A node sent a packet to an address which none of the nodes have
+    fn receive(&mut self, _packet: &[MemoryCell]) {}
+
+    // Every node is polling for packets which aren't coming. Returns a packet to send in to
+    // the network, or None to stop it.
+    fn idle(&mut self) -> Option<Vec<MemoryCell>> {
+        None
+    }
+}
+
+// Drops any packet sent outside the network, and stops it once it goes idle
+pub struct NoGateway;
+
+impl Gateway for NoGateway {}
+
+#[derive(Debug, PartialEq)]
+pub enum NetworkError {
+    UnknownNode(String),
+    DuplicateNode(String),
+    InvalidTopology(String),
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum NetworkOutcome {
+    // Every node ran to completion
+    Halted,
+    // Some nodes were still waiting on input that could never arrive
+    Deadlocked,
+    // The gateway stopped the network while it was idle
+    Stopped,
+}
+
+pub struct NetworkResult {
+    names: Vec<String>,
+    outputs: Vec<Vec<MemoryCell>>,
+    halted: Vec<bool>,
+    outcome: NetworkOutcome,
+}
+
+impl NetworkResult {
+    pub fn outcome(&self) -> NetworkOutcome {
+        self.outcome
+    }
+
+    // Everything the node wrote, in order, whether or not anyone received it
+    pub fn outputs(&self, name: &str) -> Option<&[MemoryCell]> {
+        let idx = self.names.iter().position(|n| n == name)?;
+        Some(&self.outputs[idx])
+    }
+
+    pub fn has_halted(&self, name: &str) -> Option<bool> {
+        let idx = self.names.iter().position(|n| n == name)?;
+        Some(self.halted[idx])
+    }
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+impl Network {
+    pub fn new() -> Network {
+        Network {
+            names: Vec::new(),
+            programs: Vec::new(),
+            edges: Vec::new(),
+            initial_inputs: Vec::new(),
+            packet_size: None,
+        }
+    }
+
+    // Build a network where every node runs the same program.
+    // Statements are separated by ';', and each is a chain of groups joined by "->".
+    // Every node in a group is connected to every node in the next group, so
+    // "A -> B -> A" is a loop and "A -> B, C" fans out from A to both B and C.
+    pub fn from_topology(topology: &str, program: &[MemoryCell]) -> Result<Network, NetworkError> {
+        let mut network = Network::new();
+        let invalid = || NetworkError::InvalidTopology(topology.to_string());
+
+        for statement in topology.split(';').filter(|s| !s.trim().is_empty()) {
+            let groups: Vec<Vec<&str>> = statement
+                .split("->")
+                .map(|g| g.split(',').map(|n| n.trim()).collect())
+                .collect();
+
+            for name in groups.iter().flatten() {
+                if !is_valid_name(name) {
+                    return Err(invalid());
+                }
+                if network.node(name).is_none() {
+                    network.add_node(name, program)?;
+                }
+            }
+
+            for pair in groups.windows(2) {
+                for from in &pair[0] {
+                    for to in &pair[1] {
+                        network.connect(from, to)?;
+                    }
+                }
+            }
+        }
+
+        if network.names.is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(network)
+    }
+
+    pub fn add_node(&mut self, name: &str, program: &[MemoryCell]) -> Result<(), NetworkError> {
+        if self.node(name).is_some() {
+            return Err(NetworkError::DuplicateNode(name.to_string()));
+        }
+
+        self.names.push(name.to_string());
+        self.programs.push(Vec::from(program));
+        self.edges.push(Vec::new());
+        self.initial_inputs.push(Vec::new());
+        Ok(())
+    }
+
+    fn node(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| n == name)
+    }
+
+    fn find(&self, name: &str) -> Result<usize, NetworkError> {
+        self.node(name)
+            .ok_or_else(|| NetworkError::UnknownNode(name.to_string()))
+    }
+
+    pub fn connect(&mut self, from: &str, to: &str) -> Result<(), NetworkError> {
+        let from = self.find(from)?;
+        let to = self.find(to)?;
+        if !self.edges[from].contains(&to) {
+            self.edges[from].push(to);
+        }
+        Ok(())
+    }
+
+    // Give a node some input before the network starts
+    pub fn queue(&mut self, name: &str, values: &[MemoryCell]) -> Result<(), NetworkError> {
+        let idx = self.find(name)?;
+        self.initial_inputs[idx].extend_from_slice(values);
+        Ok(())
+    }
+
+    // Rather than following the edges, send every node's output as packets of `size` values,
+    // the first of which is the address of the node to deliver the rest to. A node's address
+    // is its position in the order the nodes were added. Input never blocks either, and a
+    // node which polls while nothing is queued for it reads -1.
+    pub fn route_packets(&mut self, size: usize) {
+        assert!(size > 1, "Packets need an address and at least one value");
+        self.packet_size = Some(size);
+    }
+
+    // Run every node until they've all halted, or the network has deadlocked
+    pub fn run(self) -> IntCodeResult<NetworkResult> {
+        self.run_with_gateway(&mut NoGateway)
+    }
+
+    // As with run, but the gateway is given every packet sent outside of the network,
+    // and decides what to do whenever it goes idle
+    pub fn run_with_gateway<G: Gateway>(self, gateway: &mut G) -> IntCodeResult<NetworkResult> {
+        let count = self.names.len();
+        let monitor = Arc::new(Monitor::new(count));
+
+        let (senders, receivers): (Vec<Sender<MemoryCell>>, Vec<Receiver<MemoryCell>>) =
+            (0..count).map(|_| channel()).unzip();
+
+        {
+            let mut state = monitor.state.lock().unwrap();
+            for (idx, values) in self.initial_inputs.iter().enumerate() {
+                for value in values {
+                    senders[idx].send(*value).unwrap();
+                    state.pending[idx] += 1;
+                }
+            }
+        }
+
+        let mut computers = Vec::with_capacity(count);
+        for (idx, receiver) in receivers.into_iter().enumerate() {
+            let input = ChannelInput {
+                node: idx,
+                receiver,
+                buffered: None,
+                polls: self.packet_size.is_some(),
+                monitor: monitor.clone(),
+            };
+            let routing = match self.packet_size {
+                Some(size) => Routing::Packets(PacketOutput::new(size), senders.clone()),
+                None => Routing::Edges(
+                    self.edges[idx]
+                        .iter()
+                        .map(|&t| (t, senders[t].clone()))
+                        .collect(),
+                ),
+            };
+            let output = ChannelOutput {
+                routing,
+                history: Vec::new(),
+                monitor: monitor.clone(),
+            };
+            let storage = PagedStorage::from(self.programs[idx].as_slice());
+            computers.push(Computer::new(storage, input, output));
+        }
+
+        let results: Vec<IntCodeResult<(bool, Vec<MemoryCell>)>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = computers
+                .into_iter()
+                .enumerate()
+                .map(|(idx, computer)| {
+                    let monitor = monitor.clone();
+                    scope.spawn(move || {
+                        let _finished = FinishedGuard { node: idx, monitor };
+                        run_node(computer)
+                    })
+                })
+                .collect();
+
+            // Meanwhile this thread stands in for the gateway
+            let _stop = StopGuard(&monitor);
+            let mut state = monitor.state.lock().unwrap();
+            loop {
+                while let Some(packet) = state.unrouted.pop_front() {
+                    gateway.receive(&packet);
+                }
+                if state.finished.iter().all(|f| *f) {
+                    break;
+                }
+                if !state.stopped && state.is_idle() {
+                    match gateway.idle() {
+                        Some(packet) => route(&mut state, &senders, packet),
+                        None => {
+                            state.stopped = true;
+                            monitor.changed.notify_all();
+                        }
+                    }
+                    continue;
+                }
+                state = monitor.changed.wait(state).unwrap();
+            }
+            drop(state);
+
+            handles
+                .into_iter()
+                .map(|h| h.join().expect("Network node panicked"))
+                .collect()
+        });
+
+        let mut outputs = Vec::with_capacity(count);
+        let mut halted = Vec::with_capacity(count);
+        for result in results {
+            let (has_halted, history) = result?;
+            halted.push(has_halted);
+            outputs.push(history);
+        }
+
+        let stopped = monitor.state.lock().unwrap().stopped;
+        let outcome = match (halted.iter().all(|h| *h), stopped) {
+            (true, _) => NetworkOutcome::Halted,
+            (false, true) => NetworkOutcome::Stopped,
+            (false, false) => NetworkOutcome::Deadlocked,
+        };
+
+        Ok(NetworkResult {
+            names: self.names,
+            outputs,
+            halted,
+            outcome,
+        })
+    }
+}
+
+impl Default for Network {
+    fn default() -> Self {
+        Network::new()
+    }
+}
+
+type NodeComputer = Computer<PagedStorage, ChannelInput, ChannelOutput>;
+
+// Returns whether the node halted, and everything it output
+fn run_node(mut computer: NodeComputer) -> IntCodeResult<(bool, Vec<MemoryCell>)> {
+    loop {
+        match computer.resume()? {
+            StepResult::Halt => break,
+            StepResult::WaitingOnInput => {
+                if !computer.input().wait() {
+                    break;
+                }
+            }
+            other => panic!("Network node stopped unexpectedly: {:?}", other),
+        }
+    }
+
+    let halted = computer.has_halted();
+    Ok((halted, std::mem::take(&mut computer.output().history)))
+}
+
+struct MonitorState {
+    // How many values have been sent to each node, but not yet received
+    pending: Vec<usize>,
+    waiting: Vec<bool>,
+    // How many times each node has polled for input and found nothing, since it last got some
+    idle_reads: Vec<usize>,
+    finished: Vec<bool>,
+    // Packets for the gateway to deal with
+    unrouted: VecDeque<Vec<MemoryCell>>,
+    deadlocked: bool,
+    stopped: bool,
+}
+
+impl MonitorState {
+    // Nothing can ever happen again if every node that is still running is
+    // waiting on input, and none of them have any on the way
+    fn is_stuck(&self) -> bool {
+        let mut any_waiting = false;
+        for idx in 0..self.pending.len() {
+            if self.finished[idx] {
+                continue;
+            }
+            if !self.waiting[idx] || self.pending[idx] > 0 {
+                return false;
+            }
+            any_waiting = true;
+        }
+        any_waiting
+    }
+
+    // A packet routing network is idle once every node that is still running has polled for
+    // input at least twice without finding any, and nothing is on its way to any of them
+    fn is_idle(&self) -> bool {
+        self.unrouted.is_empty()
+            && (0..self.pending.len()).all(|idx| {
+                self.finished[idx] || (self.idle_reads[idx] >= 2 && self.pending[idx] == 0)
+            })
+    }
+}
+
+// Send a packet to the node at its address, or leave it for the gateway if there isn't one
+fn route(state: &mut MonitorState, senders: &[Sender<MemoryCell>], packet: Vec<MemoryCell>) {
+    let target = usize::try_from(packet[0])
+        .ok()
+        .filter(|t| *t < senders.len());
+    let Some(target) = target else {
+        state.unrouted.push_back(packet);
+        return;
+    };
+
+    for value in &packet[1..] {
+        // A node that has already finished has dropped its receiver, and the value is lost
+        if senders[target].send(*value).is_ok() {
+            state.pending[target] += 1;
+        }
+    }
+}
+
+// Shared between every node, so that they can tell when the network has deadlocked
+struct Monitor {
+    state: Mutex<MonitorState>,
+    changed: Condvar,
+}
+
+impl Monitor {
+    fn new(count: usize) -> Monitor {
+        Monitor {
+            state: Mutex::new(MonitorState {
+                pending: vec![0; count],
+                waiting: vec![false; count],
+                idle_reads: vec![0; count],
+                finished: vec![false; count],
+                unrouted: VecDeque::new(),
+                deadlocked: false,
+                stopped: false,
+            }),
+            changed: Condvar::new(),
+        }
+    }
+}
+
+struct FinishedGuard {
+    node: usize,
+    monitor: Arc<Monitor>,
+}
+
+// Runs even if the node fails, so the rest of the network isn't left waiting on it
+impl Drop for FinishedGuard {
+    fn drop(&mut self) {
+        let mut state = match self.monitor.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        state.finished[self.node] = true;
+        if state.is_stuck() {
+            state.deadlocked = true;
+        }
+        self.monitor.changed.notify_all();
+    }
+}
+
+struct StopGuard<'a>(&'a Monitor);
+
+// If the gateway panics, the nodes need stopping, or they'd never be joined
+impl Drop for StopGuard<'_> {
+    fn drop(&mut self) {
+        if !std::thread::panicking() {
+            return;
+        }
+        let mut state = match self.0.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        state.stopped = true;
+        self.0.changed.notify_all();
+    }
+}
+
+pub struct ChannelInput {
+    node: usize,
+    receiver: Receiver<MemoryCell>,
+    buffered: Option<MemoryCell>,
+    // Whether to read -1 when nothing has arrived, rather than waiting
+    polls: bool,
+    monitor: Arc<Monitor>,
+}
+
+impl ChannelInput {
+    // Block until a value arrives, returning false if the network has deadlocked instead
+    fn wait(&mut self) -> bool {
+        if self.buffered.is_some() {
+            return true;
+        }
+
+        let mut state = self.monitor.state.lock().unwrap();
+        loop {
+            if state.stopped {
+                return false;
+            }
+
+            if let Ok(value) = self.receiver.try_recv() {
+                state.pending[self.node] -= 1;
+                state.waiting[self.node] = false;
+                self.buffered = Some(value);
+                return true;
+            }
+
+            if state.deadlocked {
+                return false;
+            }
+
+            state.waiting[self.node] = true;
+            if state.is_stuck() {
+                state.deadlocked = true;
+                self.monitor.changed.notify_all();
+                return false;
+            }
+
+            state = self.monitor.changed.wait(state).unwrap();
+        }
+    }
+}
+
+impl InputSource for ChannelInput {
+    fn next(&mut self) -> Option<MemoryCell> {
+        if let Some(value) = self.buffered.take() {
+            return Some(value);
+        }
+
+        let mut state = self.monitor.state.lock().unwrap();
+        if state.stopped {
+            return None;
+        }
+
+        match self.receiver.try_recv() {
+            Ok(value) => {
+                state.pending[self.node] -= 1;
+                state.idle_reads[self.node] = 0;
+                Some(value)
+            }
+            Err(_) if self.polls => {
+                state.idle_reads[self.node] += 1;
+                if state.idle_reads[self.node] == 2 {
+                    self.monitor.changed.notify_all();
+                }
+                drop(state);
+                // Give the nodes with something to do a chance to do it
+                std::thread::yield_now();
+                Some(-1)
+            }
+            Err(_) => None,
+        }
+    }
+}
+
+enum Routing {
+    // Every value goes to each of the connected nodes
+    Edges(Vec<(usize, Sender<MemoryCell>)>),
+    // Values are gathered up in to packets, which go to the node at their address
+    Packets(PacketOutput, Vec<Sender<MemoryCell>>),
+}
+
+pub struct ChannelOutput {
+    routing: Routing,
+    history: Vec<MemoryCell>,
+    monitor: Arc<Monitor>,
+}
+
+impl OutputSink for ChannelOutput {
+    fn write(&mut self, value: MemoryCell) {
+        self.history.push(value);
+
+        // Sending while holding the lock means a waiting node can't miss the wake up
+        let mut state = self.monitor.state.lock().unwrap();
+        match &mut self.routing {
+            Routing::Edges(targets) => {
+                for (target, sender) in targets.iter() {
+                    // A node that has already finished has dropped its receiver
+                    if sender.send(value).is_ok() {
+                        state.pending[*target] += 1;
+                    }
+                }
+            }
+            Routing::Packets(packets, senders) => {
+                packets.write(value);
+                if let Some(packet) = packets.pop() {
+                    route(&mut state, senders, packet);
+                }
+            }
+        }
+        self.monitor.changed.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+
+    // Read two numbers, output their sum, forever
+    const ADDER: &str = "
+        loop:   in   [a]
+                in   [b]
+                add  [a], [b], [a]
+                out  [a]
+                jt   #1, #loop
+        a:      data 0
+        b:      data 0
+    ";
+
+    // Echo every input doubled, until it reads a zero
+    const DOUBLER: &str = "
+        loop:   in   [x]
+                jf   [x], #end
+                mul  [x], #2, [x]
+                out  [x]
+                jt   #1, #loop
+        end:    hlt
+        x:      data 0
+    ";
+
+    // Read an address, then pass on every packet to address 9 with the address added to Y.
+    // Address 0 starts things off by sending 7, 8 to address 1.
+    const FORWARDER: &str = "
+                in   [address]
+                jt   [address], #poll
+                out  #1
+                out  #7
+                out  #8
+        poll:   in   [x]
+                eq   [x], #-1, [empty]
+                jt   [empty], #poll
+                in   [y]
+                add  [y], [address], [y]
+                out  #9
+                out  [x]
+                out  [y]
+                jt   #1, #poll
+        address: data 0
+        x:      data 0
+        y:      data 0
+        empty:  data 0
+    ";
+
+    // Sends a packet to address 1 the first time the network goes idle, then stops it
+    #[derive(Default)]
+    struct Collector {
+        received: Vec<Vec<MemoryCell>>,
+        idle_count: usize,
+    }
+
+    impl Gateway for Collector {
+        fn receive(&mut self, packet: &[MemoryCell]) {
+            self.received.push(packet.to_vec());
+        }
+
+        fn idle(&mut self) -> Option<Vec<MemoryCell>> {
+            self.idle_count += 1;
+            match self.idle_count {
+                1 => Some(vec![1, 1, 1]),
+                _ => None,
+            }
+        }
+    }
+
+    #[test]
+    fn parses_topologies() {
+        let program = assemble(DOUBLER).unwrap();
+        let network = Network::from_topology("A -> B, C; C -> D -> A", &program).unwrap();
+        assert_eq!(vec!["A", "B", "C", "D"], network.names);
+        assert_eq!(vec![vec![1, 2], vec![], vec![3], vec![0]], network.edges);
+
+        assert_eq!(
+            Some(NetworkError::InvalidTopology("A -> ".to_string())),
+            Network::from_topology("A -> ", &program).err()
+        );
+        assert_eq!(
+            Some(NetworkError::UnknownNode("Z".to_string())),
+            Network::from_topology("A", &program)
+                .unwrap()
+                .queue("Z", &[1])
+                .err()
+        );
+    }
+
+    #[test]
+    fn runs_a_chain_with_fan_out() {
+        let program = assemble(DOUBLER).unwrap();
+        let mut network = Network::from_topology("A -> B, C", &program).unwrap();
+        network.queue("A", &[1, 5, 0]).unwrap();
+        network.queue("B", &[0]).unwrap();
+
+        let result = network.run().unwrap();
+        assert_eq!(NetworkOutcome::Deadlocked, result.outcome());
+        assert_eq!(Some(&[2, 10][..]), result.outputs("A"));
+        assert_eq!(Some(&[][..]), result.outputs("B"));
+        assert_eq!(Some(&[4, 20][..]), result.outputs("C"));
+        assert_eq!(Some(true), result.has_halted("B"));
+        assert_eq!(Some(false), result.has_halted("C"));
+    }
+
+    #[test]
+    fn detects_deadlock_in_a_loop() {
+        let mut network = Network::new();
+        network.add_node("X", &assemble(ADDER).unwrap()).unwrap();
+        network.add_node("Y", &assemble(DOUBLER).unwrap()).unwrap();
+        network.connect("X", "Y").unwrap();
+        network.connect("Y", "X").unwrap();
+        network.queue("X", &[1, 2, 3]).unwrap();
+
+        // X outputs 3, Y doubles it to 6, X outputs 9, Y doubles it to 18,
+        // and then X is waiting on a second number that will never come
+        let result = network.run().unwrap();
+        assert_eq!(NetworkOutcome::Deadlocked, result.outcome());
+        assert_eq!(Some(&[3, 9][..]), result.outputs("X"));
+        assert_eq!(Some(&[6, 18][..]), result.outputs("Y"));
+    }
+
+    #[test]
+    fn routes_packets_by_address() {
+        let program = assemble(FORWARDER).unwrap();
+        let mut network = Network::from_topology("A; B", &program).unwrap();
+        network.route_packets(3);
+        network.queue("A", &[0]).unwrap();
+        network.queue("B", &[1]).unwrap();
+
+        let mut gateway = Collector::default();
+        let result = network.run_with_gateway(&mut gateway).unwrap();
+        assert_eq!(NetworkOutcome::Stopped, result.outcome());
+        assert_eq!(vec![vec![9, 7, 9], vec![9, 1, 2]], gateway.received);
+        assert_eq!(2, gateway.idle_count);
+        assert_eq!(Some(&[1, 7, 8][..]), result.outputs("A"));
+        assert_eq!(Some(false), result.has_halted("B"));
+    }
+
+    struct Panicker;
+
+    impl Gateway for Panicker {
+        fn receive(&mut self, _packet: &[MemoryCell]) {
+            panic!("Nowhere to send it");
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Nowhere to send it")]
+    fn a_panicking_gateway_stops_the_network() {
+        let program = assemble(FORWARDER).unwrap();
+        let mut network = Network::from_topology("A; B", &program).unwrap();
+        network.route_packets(3);
+        network.queue("A", &[0]).unwrap();
+        network.queue("B", &[1]).unwrap();
+        network.run_with_gateway(&mut Panicker).unwrap();
+    }
+}
@@ -0,0 +1,508 @@
+use super::computer::Operation;
+use super::io::InputSource;
+use super::tracer::{NoTracer, Tracer};
+use super::{MemoryCell, MemoryPointer};
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum RecordedEvent {
+    // `instruction` is how many instructions had completed before the one doing the I/O
+    Input {
+        instruction: usize,
+        value: MemoryCell,
+    },
+    Output {
+        instruction: usize,
+        value: MemoryCell,
+    },
+}
+
+// Every value a program consumed and produced, in order
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Recording {
+    events: Vec<RecordedEvent>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RecordingParseError;
+
+const HEADER: &str = "intcode-recording v1";
+
+impl Recording {
+    pub fn events(&self) -> &[RecordedEvent] {
+        &self.events
+    }
+
+    pub fn inputs(&self) -> impl Iterator<Item = MemoryCell> + '_ {
+        self.events.iter().filter_map(|e| match e {
+            RecordedEvent::Input { value, .. } => Some(*value),
+            _ => None,
+        })
+    }
+
+    pub fn outputs(&self) -> impl Iterator<Item = MemoryCell> + '_ {
+        self.events.iter().filter_map(|e| match e {
+            RecordedEvent::Output { value, .. } => Some(*value),
+            _ => None,
+        })
+    }
+
+    pub fn save(&self, path: &str) -> std::io::Result<()> {
+        std::fs::write(path, self.to_string())
+    }
+
+    pub fn load(path: &str) -> std::io::Result<Recording> {
+        std::fs::read_to_string(path)?.parse().map_err(|_| {
+            std::io::Error::new(std::io::ErrorKind::InvalidData, "Not a valid recording")
+        })
+    }
+}
+
+impl fmt::Display for Recording {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", HEADER)?;
+        for event in &self.events {
+            match event {
+                RecordedEvent::Input { instruction, value } => {
+                    writeln!(f, "in {} {}", instruction, value)?
+                }
+                RecordedEvent::Output { instruction, value } => {
+                    writeln!(f, "out {} {}", instruction, value)?
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Recording {
+    type Err = RecordingParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines();
+        if lines.next() != Some(HEADER) {
+            return Err(RecordingParseError);
+        }
+
+        let events = lines
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                let parts: Vec<&str> = line.split(' ').collect();
+                let (kind, instruction, value) = match parts[..] {
+                    [kind, instruction, value] => (kind, instruction, value),
+                    _ => return Err(RecordingParseError),
+                };
+                let instruction = instruction.parse().map_err(|_| RecordingParseError)?;
+                let value = value.parse().map_err(|_| RecordingParseError)?;
+                match kind {
+                    "in" => Ok(RecordedEvent::Input { instruction, value }),
+                    "out" => Ok(RecordedEvent::Output { instruction, value }),
+                    _ => Err(RecordingParseError),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Recording { events })
+    }
+}
+
+// Passes every event on to the inner tracer, apart from input and output,
+// which the implementation handles itself before passing them on
+macro_rules! forward_to_inner {
+    () => {
+        fn operation(&mut self, address: MemoryPointer, operation: &Operation) {
+            self.inner.operation(address, operation);
+        }
+        fn parameter(&mut self, index: usize, value: MemoryCell) {
+            self.inner.parameter(index, value);
+        }
+        fn memory_write(&mut self, address: MemoryPointer, value: MemoryCell) {
+            self.inner.memory_write(address, value);
+        }
+        fn jump(&mut self, from: MemoryPointer, to: MemoryPointer) {
+            self.inner.jump(from, to);
+        }
+        fn relative_base(&mut self, from: MemoryCell, to: MemoryCell) {
+            self.inner.relative_base(from, to);
+        }
+        fn blocked_on_input(&mut self, address: MemoryPointer) {
+            self.inner.blocked_on_input(address);
+        }
+    };
+}
+
+// A Tracer which logs every value the Computer consumes and produces, whatever its input
+// and output are, stamped with the Computer's instruction count.
+// It can wrap another tracer, which still sees every event.
+pub struct Recorder<T: Tracer = NoTracer> {
+    events: Vec<RecordedEvent>,
+    inner: T,
+}
+
+impl Recorder {
+    pub fn new() -> Recorder {
+        Recorder::wrapping(NoTracer)
+    }
+}
+
+impl Default for Recorder {
+    fn default() -> Self {
+        Recorder::new()
+    }
+}
+
+impl<T: Tracer> Recorder<T> {
+    pub fn wrapping(inner: T) -> Recorder<T> {
+        Recorder {
+            events: Vec::new(),
+            inner,
+        }
+    }
+
+    pub fn inner(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    // Everything recorded so far
+    pub fn recording(&self) -> Recording {
+        Recording {
+            events: self.events.clone(),
+        }
+    }
+}
+
+impl<T: Tracer> Tracer for Recorder<T> {
+    fn input(&mut self, instruction: usize, value: MemoryCell) {
+        self.events
+            .push(RecordedEvent::Input { instruction, value });
+        self.inner.input(instruction, value);
+    }
+
+    fn output(&mut self, instruction: usize, value: MemoryCell) {
+        self.events
+            .push(RecordedEvent::Output { instruction, value });
+        self.inner.output(instruction, value);
+    }
+
+    forward_to_inner!();
+}
+
+// A recorded value, along with where it came in the whole recording
+#[derive(Clone, Copy)]
+struct Expected {
+    position: usize,
+    instruction: usize,
+    value: MemoryCell,
+}
+
+// The inputs and outputs are kept apart, so finding the next of either is quick
+struct ReplayState {
+    inputs: Vec<Expected>,
+    outputs: Vec<Expected>,
+    next_input: usize,
+    next_output: usize,
+}
+
+// Feeds a recording's inputs back to a Computer, and panics as soon as an output differs
+// from the one recorded, or any input or output happens at a different instruction.
+// Give the Computer the replayer's input, and attach the replayer as its tracer.
+// Like the Recorder, it can wrap another tracer.
+pub struct Replayer<T: Tracer = NoTracer> {
+    state: Rc<RefCell<ReplayState>>,
+    inner: T,
+}
+
+pub struct ReplayInput {
+    state: Rc<RefCell<ReplayState>>,
+}
+
+impl Replayer {
+    pub fn new(recording: Recording) -> Replayer {
+        Replayer::wrapping(recording, NoTracer)
+    }
+}
+
+impl<T: Tracer> Replayer<T> {
+    pub fn wrapping(recording: Recording, inner: T) -> Replayer<T> {
+        let mut inputs = Vec::new();
+        let mut outputs = Vec::new();
+        for (position, event) in recording.events.into_iter().enumerate() {
+            match event {
+                RecordedEvent::Input { instruction, value } => inputs.push(Expected {
+                    position,
+                    instruction,
+                    value,
+                }),
+                RecordedEvent::Output { instruction, value } => outputs.push(Expected {
+                    position,
+                    instruction,
+                    value,
+                }),
+            }
+        }
+
+        Replayer {
+            state: Rc::new(RefCell::new(ReplayState {
+                inputs,
+                outputs,
+                next_input: 0,
+                next_output: 0,
+            })),
+            inner,
+        }
+    }
+
+    pub fn input(&self) -> ReplayInput {
+        ReplayInput {
+            state: self.state.clone(),
+        }
+    }
+
+    pub fn inner(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    // True once every recorded input has been consumed, and every output seen
+    pub fn is_complete(&self) -> bool {
+        let state = self.state.borrow();
+        state.next_input == state.inputs.len() && state.next_output == state.outputs.len()
+    }
+}
+
+impl InputSource for ReplayInput {
+    fn next(&mut self) -> Option<MemoryCell> {
+        let mut state = self.state.borrow_mut();
+        let expected = *state.inputs.get(state.next_input)?;
+        state.next_input += 1;
+        Some(expected.value)
+    }
+}
+
+impl<T: Tracer> Tracer for Replayer<T> {
+    // The input has just been taken from the recording, so only its timing needs checking
+    fn input(&mut self, now: usize, value: MemoryCell) {
+        {
+            let state = self.state.borrow();
+            let expected = state.inputs[state.next_input - 1];
+            if now != expected.instruction {
+                panic!(
+                    "Replay diverged at event {}: input {} was read at instruction {}, but was recorded at instruction {}",
+                    expected.position, value, now, expected.instruction
+                );
+            }
+        }
+        self.inner.input(now, value);
+    }
+
+    fn output(&mut self, now: usize, value: MemoryCell) {
+        {
+            let mut state = self.state.borrow_mut();
+            let expected = match state.outputs.get(state.next_output) {
+                Some(expected) => *expected,
+                None => panic!(
+                    "Replay diverged: output {} at instruction {} is past the end of the recording",
+                    value, now
+                ),
+            };
+
+            if value != expected.value {
+                panic!(
+                    "Replay diverged at event {}: output was {}, but the recording has {}",
+                    expected.position, value, expected.value
+                );
+            }
+            if now != expected.instruction {
+                panic!(
+                    "Replay diverged at event {}: output {} was written at instruction {}, but was recorded at instruction {}",
+                    expected.position, value, now, expected.instruction
+                );
+            }
+
+            state.next_output += 1;
+        }
+        self.inner.output(now, value);
+    }
+
+    forward_to_inner!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+
+    const ADDER: &str = "
+        loop:   in   [a]
+                jf   [a], #end
+                add  [a], [total], [total]
+                out  [total]
+                jt   #1, #loop
+        end:    hlt
+        a:      data 0
+        total:  data 0
+    ";
+
+    fn record(inputs: &[MemoryCell]) -> Recording {
+        let mut computer = Computer::new(
+            assemble(ADDER).unwrap(),
+            BufferInput::new(4),
+            BufferOutput::new(4),
+        )
+        .with_tracer(Recorder::new());
+
+        // Feed the inputs one at a time, so the computer blocks in between
+        for value in inputs {
+            assert_eq!(StepResult::WaitingOnInput, computer.resume().unwrap());
+            computer.input().queue(*value);
+        }
+        computer.run_until_halt().unwrap();
+        computer.tracer().recording()
+    }
+
+    #[test]
+    fn records_io_with_instruction_counts() {
+        let recording = record(&[3, 4, 0]);
+        assert_eq!(
+            &[
+                RecordedEvent::Input {
+                    instruction: 0,
+                    value: 3
+                },
+                RecordedEvent::Output {
+                    instruction: 3,
+                    value: 3
+                },
+                RecordedEvent::Input {
+                    instruction: 5,
+                    value: 4
+                },
+                RecordedEvent::Output {
+                    instruction: 8,
+                    value: 7
+                },
+                RecordedEvent::Input {
+                    instruction: 10,
+                    value: 0
+                },
+            ],
+            recording.events()
+        );
+
+        assert_eq!(Ok(recording.clone()), recording.to_string().parse());
+        assert_eq!(
+            Err(RecordingParseError),
+            "intcode-recording v1\nin 1".parse::<Recording>()
+        );
+    }
+
+    #[test]
+    fn stamps_match_the_computers_instruction_count() {
+        let mut computer = Computer::new(
+            assemble(ADDER).unwrap(),
+            BufferInput::new(1),
+            BufferOutput::new(1),
+        )
+        .with_tracer(Recorder::new());
+
+        // Steps which block on input don't execute anything
+        for _ in 0..3 {
+            assert_eq!(StepResult::WaitingOnInput, computer.step().unwrap());
+        }
+        computer.input().queue(9);
+        while computer.tracer().recording().outputs().count() == 0 {
+            computer.step().unwrap();
+        }
+
+        assert_eq!(4, computer.instruction_count());
+        assert_eq!(
+            &[
+                RecordedEvent::Input {
+                    instruction: 0,
+                    value: 9
+                },
+                RecordedEvent::Output {
+                    instruction: 3,
+                    value: 9
+                },
+            ],
+            computer.tracer().recording().events()
+        );
+    }
+
+    #[test]
+    fn replays_a_recording() {
+        let recording = record(&[3, 4, 0]);
+        let path = std::env::temp_dir().join("nickwb_advent_2019_recording_test.txt");
+        let path = path.to_str().unwrap();
+        recording.save(path).unwrap();
+        let recording = Recording::load(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        let replayer = Replayer::new(recording);
+        let mut computer = Computer::new(
+            assemble(ADDER).unwrap(),
+            replayer.input(),
+            BufferOutput::new(4),
+        )
+        .with_tracer(replayer);
+        computer.run_until_halt().unwrap();
+        assert!(computer.tracer().is_complete());
+    }
+
+    #[test]
+    #[should_panic(expected = "output was 6, but the recording has 3")]
+    fn replay_panics_when_outputs_diverge() {
+        let replayer = Replayer::new(record(&[3, 4, 0]));
+        // Doubles the total rather than adding to it
+        let changed = ADDER.replace("add  [a], [total]", "mul  [a], #2");
+        let mut computer = Computer::new(
+            assemble(&changed).unwrap(),
+            replayer.input(),
+            BufferOutput::new(4),
+        )
+        .with_tracer(replayer);
+        computer.run_until_halt().unwrap();
+    }
+
+    #[test]
+    fn records_and_replays_alongside_another_tracer() {
+        let mut computer = Computer::new(
+            assemble(ADDER).unwrap(),
+            BufferInput::new(4),
+            BufferOutput::new(4),
+        )
+        .with_tracer(Recorder::wrapping(CountingTracer::new()));
+        computer.input().queue_many(&[3, 4, 0]);
+        computer.run_until_halt().unwrap();
+        assert_eq!(
+            computer.instruction_count(),
+            computer.tracer().inner().total()
+        );
+        let recording = computer.tracer().recording();
+        assert_eq!(recording, record(&[3, 4, 0]));
+
+        let replayer = Replayer::wrapping(recording, CountingTracer::new());
+        let mut computer = Computer::new(
+            assemble(ADDER).unwrap(),
+            replayer.input(),
+            BufferOutput::new(4),
+        )
+        .with_tracer(replayer);
+        computer.run_until_halt().unwrap();
+        assert!(computer.tracer().is_complete());
+        assert_eq!(
+            computer.instruction_count(),
+            computer.tracer().inner().total()
+        );
+    }
+}
@@ -0,0 +1,283 @@
+use super::{IntCodeCell, MemoryCell, MemoryPointer};
+use std::fmt;
+use std::str::FromStr;
+
+// Everything needed to put a Computer back exactly where it was.
+// Input and output are not included, as they belong to the caller.
+// Memory is kept sparse, as runs of cells by their starting address, and anything
+// outside of those runs is zero.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot<C = MemoryCell> {
+    pub(super) size: MemoryPointer,
+    pub(super) limit: MemoryPointer,
+    pub(super) memory: Vec<(MemoryPointer, Vec<C>)>,
+    pub(super) program_counter: MemoryPointer,
+    pub(super) relative_base: C,
+    pub(super) has_halted: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SnapshotParseError;
+
+const HEADER: &str = "intcode-snapshot v4";
+
+impl<C: IntCodeCell> Snapshot<C> {
+    // How many cells the storage held when the snapshot was taken
+    pub fn size(&self) -> MemoryPointer {
+        self.size
+    }
+
+    // How many cells the storage could have grown to
+    pub fn limit(&self) -> MemoryPointer {
+        self.limit
+    }
+
+    pub fn memory(&self) -> &[(MemoryPointer, Vec<C>)] {
+        &self.memory
+    }
+
+    pub fn program_counter(&self) -> MemoryPointer {
+        self.program_counter
+    }
+
+    pub fn relative_base(&self) -> C {
+        self.relative_base
+    }
+
+    pub fn has_halted(&self) -> bool {
+        self.has_halted
+    }
+
+    pub fn save(&self, path: &str) -> std::io::Result<()> {
+        std::fs::write(path, self.to_string())
+    }
+
+    pub fn load(path: &str) -> std::io::Result<Snapshot<C>> {
+        std::fs::read_to_string(path)?.parse().map_err(|_| {
+            std::io::Error::new(std::io::ErrorKind::InvalidData, "Not a valid snapshot")
+        })
+    }
+}
+
+fn join<T: fmt::Display>(values: impl Iterator<Item = T>) -> String {
+    values.map(|v| v.to_string()).collect::<Vec<_>>().join(",")
+}
+
+impl<C: IntCodeCell> fmt::Display for Snapshot<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", HEADER)?;
+        writeln!(f, "program_counter {}", self.program_counter)?;
+        writeln!(f, "relative_base {}", self.relative_base)?;
+        writeln!(f, "halted {}", self.has_halted)?;
+        writeln!(f, "size {}", self.size)?;
+        writeln!(f, "limit {}", self.limit)?;
+        let runs = self
+            .memory
+            .iter()
+            .map(|(start, cells)| format!("{}:{}", start, join(cells.iter())));
+        writeln!(f, "memory {}", runs.collect::<Vec<_>>().join(" "))
+    }
+}
+
+fn parse_list<T: FromStr>(text: &str) -> Result<Vec<T>, SnapshotParseError> {
+    text.split(',')
+        .filter(|v| !v.is_empty())
+        .map(|v| v.parse::<T>().map_err(|_| SnapshotParseError))
+        .collect()
+}
+
+impl<C: IntCodeCell> FromStr for Snapshot<C> {
+    type Err = SnapshotParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines();
+        if lines.next() != Some(HEADER) {
+            return Err(SnapshotParseError);
+        }
+
+        // Each remaining line is a key, followed by a single space and the value
+        let mut next_value = |key: &str| {
+            let line = lines.next().ok_or(SnapshotParseError)?;
+            match line.split_once(' ') {
+                Some((k, v)) if k == key => Ok(v.to_string()),
+                _ if line == key => Ok(String::new()),
+                _ => Err(SnapshotParseError),
+            }
+        };
+
+        let program_counter = next_value("program_counter")?
+            .parse()
+            .map_err(|_| SnapshotParseError)?;
+        let relative_base = next_value("relative_base")?
+            .parse()
+            .map_err(|_| SnapshotParseError)?;
+        let has_halted = next_value("halted")?
+            .parse()
+            .map_err(|_| SnapshotParseError)?;
+        let size = next_value("size")?
+            .parse()
+            .map_err(|_| SnapshotParseError)?;
+        let limit = next_value("limit")?
+            .parse()
+            .map_err(|_| SnapshotParseError)?;
+        let memory = next_value("memory")?
+            .split(' ')
+            .filter(|run| !run.is_empty())
+            .map(|run| {
+                let (start, cells) = run.split_once(':').ok_or(SnapshotParseError)?;
+                let start = start.parse().map_err(|_| SnapshotParseError)?;
+                Ok((start, parse_list(cells)?))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Snapshot {
+            size,
+            limit,
+            memory,
+            program_counter,
+            relative_base,
+            has_halted,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+
+    fn counter() -> Computer<PagedStorage, BufferInput, BufferOutput> {
+        // Keep a running total of the inputs on a second page, and echo it after every input
+        let program = assemble(
+            "
+                    arb  #1000
+            loop:   in   [rb+0]
+                    add  [rb+0], [total], [total]
+                    out  [total]
+                    jt   #1, #loop
+            total:  data 0
+            ",
+        )
+        .unwrap();
+        Computer::new(
+            PagedStorage::from(program),
+            BufferInput::new(1),
+            BufferOutput::new(1),
+        )
+    }
+
+    #[test]
+    fn restore_rewinds_the_computer() {
+        let mut computer = counter();
+        computer.input().queue_many(&[1, 2]);
+        computer.resume().unwrap();
+        let snapshot = computer.snapshot();
+        assert_eq!(vec![1, 3], computer.output().pop_all());
+
+        computer.input().queue(10);
+        computer.resume().unwrap();
+        assert_eq!(vec![13], computer.output().pop_all());
+        assert_ne!(snapshot, computer.snapshot());
+
+        computer.restore(&snapshot).unwrap();
+        assert_eq!(snapshot, computer.snapshot());
+        computer.input().queue(20);
+        computer.resume().unwrap();
+        assert_eq!(vec![23], computer.output().pop_all());
+    }
+
+    #[test]
+    fn forks_from_a_snapshot() {
+        let mut computer = counter();
+        computer.input().queue(5);
+        computer.resume().unwrap();
+        let snapshot = computer.snapshot();
+
+        let mut fork: Computer<PagedStorage, _, _> =
+            Computer::from_snapshot(&snapshot, BufferInput::new(1), BufferOutput::new(1));
+        fork.input().queue(1);
+        fork.resume().unwrap();
+        assert_eq!(vec![6], fork.output().pop_all());
+        assert_eq!(snapshot, computer.snapshot());
+    }
+
+    #[test]
+    fn forks_keep_the_storage_limit() {
+        let program = assemble("add #7, #0, [40]\nhlt").unwrap();
+        let mut storage = PagedStorage::with_limit(50);
+        storage.load(&program).unwrap();
+        let mut computer = Computer::new(storage, NoInput, BufferOutput::new(0));
+        computer.run_until_halt().unwrap();
+
+        let snapshot = computer.snapshot();
+        assert_eq!(50, snapshot.limit());
+        let fork: Computer<PagedStorage, _, _> =
+            Computer::from_snapshot(&snapshot, NoInput, BufferOutput::new(0));
+        assert_eq!(50, fork.storage().limit());
+        assert_eq!(1, fork.storage().pages_allocated());
+        assert_eq!(7, fork.read_memory(40).unwrap());
+        assert_eq!(snapshot, fork.snapshot());
+    }
+
+    #[test]
+    fn rejects_snapshots_of_a_different_size() {
+        let snapshot = counter().snapshot();
+        let mut computer = Computer::new(vec![99], NoInput, BufferOutput::new(0));
+        let error = computer.restore(&snapshot).unwrap_err();
+        assert_eq!(IntCodeErrorKind::SnapshotSizeMismatch, error.kind());
+        assert_eq!(Some(snapshot.size() - 1), error.address());
+    }
+
+    #[test]
+    fn keeps_paged_storage_sparse() {
+        let mut computer = counter();
+        computer.input().queue(5);
+        computer.resume().unwrap();
+
+        // A single write near the end of memory
+        let far = DEFAULT_MEMORY_LIMIT - 3;
+        let program = assemble(&format!("add #7, #0, [{}]\nhlt", far)).unwrap();
+        let mut far_computer =
+            Computer::new(PagedStorage::from(program), NoInput, BufferOutput::new(0));
+        far_computer.run_until_halt().unwrap();
+
+        let snapshot = far_computer.snapshot();
+        assert_eq!(DEFAULT_MEMORY_LIMIT, snapshot.size());
+        assert_eq!(2, snapshot.memory().len());
+        assert_eq!((far, vec![7]), snapshot.memory()[1]);
+
+        // Restoring over a busier computer frees what it had allocated
+        computer.restore(&snapshot).unwrap();
+        assert_eq!(2, computer.storage().pages_allocated());
+        assert_eq!(7, computer.read_memory(far).unwrap());
+        assert_eq!(0, computer.read_memory(1000).unwrap());
+
+        let fork: Computer<PagedStorage, _, _> =
+            Computer::from_snapshot(&snapshot, NoInput, BufferOutput::new(0));
+        assert_eq!(2, fork.storage().pages_allocated());
+        assert_eq!(snapshot, fork.snapshot());
+        assert_eq!(Ok(snapshot.clone()), snapshot.to_string().parse());
+    }
+
+    #[test]
+    fn round_trips_through_text_and_disk() {
+        let mut computer = counter();
+        computer.input().queue_many(&[4, -2]);
+        computer.resume().unwrap();
+        let snapshot = computer.snapshot();
+
+        assert_eq!(Ok(snapshot.clone()), snapshot.to_string().parse());
+
+        let path = std::env::temp_dir().join("nickwb_advent_2019_snapshot_test.txt");
+        let path = path.to_str().unwrap();
+        snapshot.save(path).unwrap();
+        assert_eq!(snapshot, Snapshot::load(path).unwrap());
+        std::fs::remove_file(path).unwrap();
+
+        let tiny: Snapshot = Computer::new(vec![99], NoInput, BufferOutput::new(0)).snapshot();
+        assert_eq!(Ok(tiny.clone()), tiny.to_string().parse());
+        assert_eq!(
+            Err(SnapshotParseError),
+            "not a snapshot".parse::<Snapshot>()
+        );
+    }
+}
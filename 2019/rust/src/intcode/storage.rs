@@ -1,22 +1,175 @@
-use super::{MemoryCell, MemoryPointer};
+use super::{IntCodeCell, IntCodeError, IntCodeResult, MemoryCell, MemoryPointer};
 
-pub trait Storage {
+pub trait Storage<C: IntCodeCell = MemoryCell> {
+    // How many cells are currently backed by this storage
     fn size(&self) -> MemoryPointer;
-    fn get(&self, at: MemoryPointer) -> MemoryCell;
-    fn put(&mut self, at: MemoryPointer, value: MemoryCell) -> ();
+
+    // How many cells this storage could ever hold, if it grows as it is written
+    fn limit(&self) -> MemoryPointer {
+        self.size()
+    }
+
+    fn get(&self, at: MemoryPointer) -> IntCodeResult<C>;
+    fn put(&mut self, at: MemoryPointer, value: C) -> IntCodeResult<()>;
+
+    // Runs of cells which may hold something other than zero, along with the address each
+    // run starts at. Every cell outside of them reads as zero.
+    fn regions(&self) -> Vec<(MemoryPointer, &[C])>;
+
+    // Set every cell from `at` onwards back to zero
+    fn clear_from(&mut self, at: MemoryPointer) -> IntCodeResult<()> {
+        for idx in at..self.size() {
+            self.put(idx, C::zero())?;
+        }
+        Ok(())
+    }
 }
 
-pub type VecStorage = Vec<MemoryCell>;
+pub type VecStorage<C = MemoryCell> = Vec<C>;
 
-impl Storage for VecStorage {
+impl<C: IntCodeCell> Storage<C> for Vec<C> {
     fn size(&self) -> MemoryPointer {
         self.len()
     }
-    fn get(&self, at: MemoryPointer) -> MemoryCell {
-        self[at]
+    fn get(&self, at: MemoryPointer) -> IntCodeResult<C> {
+        self.as_slice()
+            .get(at)
+            .copied()
+            .ok_or_else(|| IntCodeError::read_out_of_bounds(at))
+    }
+    fn put(&mut self, at: MemoryPointer, value: C) -> IntCodeResult<()> {
+        let cell = self
+            .get_mut(at)
+            .ok_or_else(|| IntCodeError::write_out_of_bounds(at))?;
+        *cell = value;
+        Ok(())
+    }
+    fn regions(&self) -> Vec<(MemoryPointer, &[C])> {
+        vec![(0, self.as_slice())]
+    }
+}
+
+pub const PAGE_SIZE: MemoryPointer = 1024;
+
+// Enough for any reasonable program, without letting a runaway one eat all the RAM
+pub const DEFAULT_MEMORY_LIMIT: MemoryPointer = 1 << 20;
+
+type Page<C> = Box<[C; PAGE_SIZE]>;
+
+// Sparse storage which allocates fixed-size pages as they are first written.
+// Reading a cell that has never been written gives zero.
+pub struct PagedStorage<C: IntCodeCell = MemoryCell> {
+    pages: Vec<Option<Page<C>>>,
+    limit: MemoryPointer,
+}
+
+impl<C: IntCodeCell> PagedStorage<C> {
+    pub fn new() -> PagedStorage<C> {
+        PagedStorage::with_limit(DEFAULT_MEMORY_LIMIT)
+    }
+
+    // Addresses at or beyond the limit can't be read or written
+    pub fn with_limit(limit: MemoryPointer) -> PagedStorage<C> {
+        PagedStorage {
+            pages: Vec::new(),
+            limit,
+        }
+    }
+
+    pub fn load(&mut self, program: &[C]) -> IntCodeResult<()> {
+        for (idx, value) in program.iter().enumerate() {
+            self.put(idx, *value)?;
+        }
+        Ok(())
+    }
+
+    pub fn pages_allocated(&self) -> usize {
+        self.pages.iter().filter(|p| p.is_some()).count()
+    }
+}
+
+impl<C: IntCodeCell> Default for PagedStorage<C> {
+    fn default() -> Self {
+        PagedStorage::new()
+    }
+}
+
+impl<C: IntCodeCell> From<&[C]> for PagedStorage<C> {
+    fn from(program: &[C]) -> Self {
+        let mut storage = PagedStorage::with_limit(DEFAULT_MEMORY_LIMIT.max(program.len()));
+        storage
+            .load(program)
+            .expect("Program fits within the limit");
+        storage
+    }
+}
+
+impl<C: IntCodeCell> From<Vec<C>> for PagedStorage<C> {
+    fn from(program: Vec<C>) -> Self {
+        PagedStorage::from(program.as_slice())
+    }
+}
+
+impl<C: IntCodeCell> Storage<C> for PagedStorage<C> {
+    fn size(&self) -> MemoryPointer {
+        (self.pages.len() * PAGE_SIZE).min(self.limit)
+    }
+
+    fn limit(&self) -> MemoryPointer {
+        self.limit
+    }
+
+    fn get(&self, at: MemoryPointer) -> IntCodeResult<C> {
+        if at >= self.limit {
+            return Err(IntCodeError::read_out_of_bounds(at));
+        }
+
+        match self.pages.get(at / PAGE_SIZE) {
+            Some(Some(page)) => Ok(page[at % PAGE_SIZE]),
+            _ => Ok(C::zero()),
+        }
+    }
+
+    fn put(&mut self, at: MemoryPointer, value: C) -> IntCodeResult<()> {
+        if at >= self.limit {
+            return Err(IntCodeError::write_out_of_bounds(at));
+        }
+
+        // Unallocated pages already read as zero
+        let page_number = at / PAGE_SIZE;
+        if value == C::zero() && !matches!(self.pages.get(page_number), Some(Some(_))) {
+            return Ok(());
+        }
+
+        if page_number >= self.pages.len() {
+            self.pages.resize_with(page_number + 1, || None);
+        }
+
+        let page = self.pages[page_number].get_or_insert_with(|| Box::new([C::zero(); PAGE_SIZE]));
+        page[at % PAGE_SIZE] = value;
+        Ok(())
     }
-    fn put(&mut self, at: MemoryPointer, value: MemoryCell) {
-        self[at] = value;
+
+    // Only the allocated pages, stopping at the limit
+    fn regions(&self) -> Vec<(MemoryPointer, &[C])> {
+        self.pages
+            .iter()
+            .enumerate()
+            .filter_map(|(n, page)| {
+                let start = n * PAGE_SIZE;
+                let len = PAGE_SIZE.min(self.limit - start);
+                page.as_ref().map(|p| (start, &p[..len]))
+            })
+            .collect()
+    }
+
+    // Whole pages are freed, rather than filled with zeroes
+    fn clear_from(&mut self, at: MemoryPointer) -> IntCodeResult<()> {
+        self.pages.truncate(at.div_ceil(PAGE_SIZE));
+        if let Some(Some(page)) = self.pages.get_mut(at / PAGE_SIZE) {
+            page[at % PAGE_SIZE..].fill(C::zero());
+        }
+        Ok(())
     }
 }
 
@@ -24,15 +177,25 @@ impl Storage for VecStorage {
 pub type MutSliceStorage<'a> = &'a mut [MemoryCell];
 
 #[cfg(test)]
-impl<'a> Storage for MutSliceStorage<'a> {
+impl<'a, C: IntCodeCell> Storage<C> for &'a mut [C] {
     fn size(&self) -> MemoryPointer {
         self.len()
     }
-    fn get(&self, at: MemoryPointer) -> MemoryCell {
-        self[at]
+    fn get(&self, at: MemoryPointer) -> IntCodeResult<C> {
+        (**self)
+            .get(at)
+            .copied()
+            .ok_or_else(|| IntCodeError::read_out_of_bounds(at))
+    }
+    fn put(&mut self, at: MemoryPointer, value: C) -> IntCodeResult<()> {
+        let cell = (**self)
+            .get_mut(at)
+            .ok_or_else(|| IntCodeError::write_out_of_bounds(at))?;
+        *cell = value;
+        Ok(())
     }
-    fn put(&mut self, at: MemoryPointer, value: MemoryCell) {
-        self[at] = value;
+    fn regions(&self) -> Vec<(MemoryPointer, &[C])> {
+        vec![(0, &**self)]
     }
 }
 
@@ -40,3 +203,54 @@ impl<'a> Storage for MutSliceStorage<'a> {
 pub fn slice_storage<'a>(s: MutSliceStorage<'a>) -> MutSliceStorage<'a> {
     s
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paged_storage_grows_on_write() {
+        let mut storage: PagedStorage = PagedStorage::from(vec![1, 2, 3]);
+        assert_eq!(1, storage.pages_allocated());
+        assert_eq!(PAGE_SIZE, storage.size());
+        assert_eq!(3, storage.get(2).unwrap());
+
+        // Unwritten cells read as zero, without allocating
+        assert_eq!(0, storage.get(PAGE_SIZE * 5 + 7).unwrap());
+        assert_eq!(1, storage.pages_allocated());
+
+        // Nor does writing a zero
+        storage.put(PAGE_SIZE * 5 + 7, 0).unwrap();
+        assert_eq!(1, storage.pages_allocated());
+
+        storage.put(PAGE_SIZE * 5 + 7, 42).unwrap();
+        assert_eq!(42, storage.get(PAGE_SIZE * 5 + 7).unwrap());
+        assert_eq!(2, storage.pages_allocated());
+        assert_eq!(PAGE_SIZE * 6, storage.size());
+    }
+
+    #[test]
+    fn paged_storage_respects_the_limit() {
+        let mut storage: PagedStorage = PagedStorage::with_limit(100);
+        assert!(storage.put(99, 1).is_ok());
+        assert_eq!(
+            Err(IntCodeError::write_out_of_bounds(100)),
+            storage.put(100, 1)
+        );
+        assert_eq!(Err(IntCodeError::read_out_of_bounds(100)), storage.get(100));
+        assert_eq!(100, storage.size());
+    }
+
+    #[test]
+    fn paged_storage_frees_cleared_pages() {
+        let mut storage: PagedStorage = PagedStorage::new();
+        storage.put(10, 1).unwrap();
+        storage.put(PAGE_SIZE + 10, 2).unwrap();
+        storage.put(PAGE_SIZE * 3, 3).unwrap();
+
+        storage.clear_from(5).unwrap();
+        assert_eq!(1, storage.pages_allocated());
+        assert_eq!(0, storage.get(10).unwrap());
+        assert_eq!(0, storage.get(PAGE_SIZE * 3).unwrap());
+    }
+}
@@ -0,0 +1,260 @@
+use super::computer::{OpCode, Operation};
+use super::{IntCodeCell, MemoryCell, MemoryPointer};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+// Receives structured events as the Computer executes.
+// Every method does nothing by default, so implementations only need to handle what they care about.
+pub trait Tracer<C: IntCodeCell = MemoryCell> {
+    // An operation has finished executing, and the events it caused came just before it.
+    // This isn't sent for an operation which blocked on input or failed, but one which failed
+    // may already have sent some of its parameter and write events before the error.
+    fn operation(&mut self, _address: MemoryPointer, _operation: &Operation<C>) {}
+    // An input parameter of the operation being executed has been resolved to a value
+    fn parameter(&mut self, _index: usize, _value: C) {}
+    fn memory_write(&mut self, _address: MemoryPointer, _value: C) {}
+    // `instruction` is how many instructions had completed before the one doing the I/O
+    fn input(&mut self, _instruction: usize, _value: C) {}
+    fn output(&mut self, _instruction: usize, _value: C) {}
+    fn jump(&mut self, _from: MemoryPointer, _to: MemoryPointer) {}
+    fn relative_base(&mut self, _from: C, _to: C) {}
+    // The operation at this address couldn't get any input, and will be tried again later
+    fn blocked_on_input(&mut self, _address: MemoryPointer) {}
+}
+
+pub struct NoTracer;
+
+impl<C: IntCodeCell> Tracer<C> for NoTracer {}
+
+// Writes every event as a single line of JSON
+pub struct JsonLinesTracer<W: Write> {
+    writer: W,
+}
+
+impl JsonLinesTracer<BufWriter<File>> {
+    pub fn create(path: &str) -> std::io::Result<JsonLinesTracer<BufWriter<File>>> {
+        Ok(JsonLinesTracer::new(BufWriter::new(File::create(path)?)))
+    }
+}
+
+impl<W: Write> JsonLinesTracer<W> {
+    pub fn new(writer: W) -> JsonLinesTracer<W> {
+        JsonLinesTracer { writer }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn write_line(&mut self, args: std::fmt::Arguments) {
+        writeln!(&mut self.writer, "{}", args).expect("Failed to write trace");
+    }
+}
+
+impl<W: Write, C: IntCodeCell> Tracer<C> for JsonLinesTracer<W> {
+    fn operation(&mut self, address: MemoryPointer, operation: &Operation<C>) {
+        let inputs = operation
+            .inputs()
+            .iter()
+            .map(|p| format!("\"{}\"", p))
+            .collect::<Vec<_>>()
+            .join(",");
+        let output = match operation.output() {
+            Some(p) => format!("\"{}\"", p),
+            None => "null".to_string(),
+        };
+        self.write_line(format_args!(
+            r#"{{"event":"operation","address":{},"op":"{}","inputs":[{}],"output":{}}}"#,
+            address,
+            operation.op_code().mnemonic(),
+            inputs,
+            output
+        ));
+    }
+
+    fn parameter(&mut self, index: usize, value: C) {
+        self.write_line(format_args!(
+            r#"{{"event":"parameter","index":{},"value":{}}}"#,
+            index, value
+        ));
+    }
+
+    fn memory_write(&mut self, address: MemoryPointer, value: C) {
+        self.write_line(format_args!(
+            r#"{{"event":"write","address":{},"value":{}}}"#,
+            address, value
+        ));
+    }
+
+    fn input(&mut self, instruction: usize, value: C) {
+        self.write_line(format_args!(
+            r#"{{"event":"input","instruction":{},"value":{}}}"#,
+            instruction, value
+        ));
+    }
+
+    fn output(&mut self, instruction: usize, value: C) {
+        self.write_line(format_args!(
+            r#"{{"event":"output","instruction":{},"value":{}}}"#,
+            instruction, value
+        ));
+    }
+
+    fn jump(&mut self, from: MemoryPointer, to: MemoryPointer) {
+        self.write_line(format_args!(
+            r#"{{"event":"jump","from":{},"to":{}}}"#,
+            from, to
+        ));
+    }
+
+    fn relative_base(&mut self, from: C, to: C) {
+        self.write_line(format_args!(
+            r#"{{"event":"relative_base","from":{},"to":{}}}"#,
+            from, to
+        ));
+    }
+}
+
+// Counts how many times each op code, and each address, is executed
+#[derive(Default)]
+pub struct CountingTracer {
+    total: usize,
+    by_op_code: HashMap<OpCode, usize>,
+    by_address: HashMap<MemoryPointer, usize>,
+}
+
+impl CountingTracer {
+    pub fn new() -> CountingTracer {
+        CountingTracer::default()
+    }
+
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    pub fn by_op_code(&self) -> &HashMap<OpCode, usize> {
+        &self.by_op_code
+    }
+
+    pub fn by_address(&self) -> &HashMap<MemoryPointer, usize> {
+        &self.by_address
+    }
+
+    // Summarise the histograms, including only the hottest few addresses
+    pub fn report(&self, top_addresses: usize) -> String {
+        let mut report = format!("Instructions executed: {}\n", self.total);
+
+        let mut op_codes: Vec<(&OpCode, &usize)> = self.by_op_code.iter().collect();
+        op_codes.sort_by(|a, b| b.1.cmp(a.1).then(a.0.mnemonic().cmp(b.0.mnemonic())));
+        for (op_code, count) in op_codes {
+            report.push_str(&format!("  {:<4} {:>12}\n", op_code.mnemonic(), count));
+        }
+
+        let mut addresses: Vec<(&MemoryPointer, &usize)> = self.by_address.iter().collect();
+        addresses.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        report.push_str("Hottest addresses:\n");
+        for (address, count) in addresses.into_iter().take(top_addresses) {
+            report.push_str(&format!("  {:>6} {:>12}\n", address, count));
+        }
+
+        report
+    }
+}
+
+impl<C: IntCodeCell> Tracer<C> for CountingTracer {
+    fn operation(&mut self, address: MemoryPointer, operation: &Operation<C>) {
+        self.total += 1;
+        *self.by_op_code.entry(operation.op_code()).or_insert(0) += 1;
+        *self.by_address.entry(address).or_insert(0) += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use super::*;
+
+    const COUNTDOWN: &str = "
+                in   [n]
+        loop:   out  [n]
+                add  [n], #-1, [n]
+                jt   [n], #loop
+                arb  #5
+                hlt
+        n:      data 0
+    ";
+
+    fn traced<T: Tracer>(tracer: T) -> Computer<VecStorage, BufferInput, BufferOutput, T> {
+        let program = assemble(COUNTDOWN).unwrap();
+        let mut computer =
+            Computer::new(program, BufferInput::new(1), BufferOutput::new(1)).with_tracer(tracer);
+        computer.input().queue(2);
+        computer.run_until_halt().unwrap();
+        computer
+    }
+
+    #[test]
+    fn counts_op_codes_and_addresses() {
+        let mut computer = traced(CountingTracer::new());
+        let tracer = computer.tracer();
+
+        assert_eq!(9, tracer.total());
+        assert_eq!(Some(&2), tracer.by_op_code().get(&OpCode::Add));
+        assert_eq!(Some(&1), tracer.by_op_code().get(&OpCode::Halt));
+        assert_eq!(Some(&1), tracer.by_address().get(&0));
+        assert_eq!(Some(&2), tracer.by_address().get(&8));
+        assert!(tracer.report(3).starts_with("Instructions executed: 9\n"));
+    }
+
+    #[test]
+    fn does_not_count_operations_blocked_on_input() {
+        let program = assemble(COUNTDOWN).unwrap();
+        let mut computer = Computer::new(program, BufferInput::new(1), BufferOutput::new(1))
+            .with_tracer(CountingTracer::new());
+        assert_eq!(StepResult::WaitingOnInput, computer.step().unwrap());
+        assert_eq!(StepResult::WaitingOnInput, computer.step().unwrap());
+
+        computer.input().queue(2);
+        computer.run_until_halt().unwrap();
+        assert_eq!(computer.instruction_count(), computer.tracer().total());
+        assert_eq!(Some(&1), computer.tracer().by_address().get(&0));
+    }
+
+    #[test]
+    fn does_not_count_failed_operations() {
+        // Op code 42 doesn't exist
+        let program: &mut [MemoryCell] = &mut [1101, 1, 2, 5, 42, 0];
+        let mut computer = Computer::new(
+            slice_storage(program),
+            BufferInput::new(1),
+            BufferOutput::new(1),
+        )
+        .with_tracer(CountingTracer::new());
+        assert!(computer.run_until_halt().is_err());
+        assert_eq!(1, computer.instruction_count());
+        assert_eq!(1, computer.tracer().total());
+    }
+
+    #[test]
+    fn writes_json_lines() {
+        let computer = traced(JsonLinesTracer::new(Vec::new()));
+        let written = computer.into_tracer().into_inner();
+        let text = String::from_utf8(written).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(r#"{"event":"input","instruction":0,"value":2}"#, lines[0]);
+        assert_eq!(r#"{"event":"write","address":14,"value":2}"#, lines[1]);
+        assert_eq!(
+            r#"{"event":"operation","address":0,"op":"in","inputs":[],"output":"[14]"}"#,
+            lines[2]
+        );
+        assert!(lines.contains(&r#"{"event":"output","instruction":4,"value":1}"#));
+        assert!(lines.contains(&r#"{"event":"jump","from":8,"to":2}"#));
+        assert!(lines.contains(&r#"{"event":"relative_base","from":0,"to":5}"#));
+        assert_eq!(
+            r#"{"event":"operation","address":13,"op":"hlt","inputs":[],"output":null}"#,
+            *lines.last().unwrap()
+        );
+    }
+}
@@ -1,4 +1,4 @@
-mod intcode;
+pub mod intcode;
 mod util;
 
 pub mod day1;
@@ -10,7 +10,15 @@ pub mod day14;
 pub mod day15;
 pub mod day16;
 pub mod day17;
+pub mod day18;
+pub mod day19;
 pub mod day2;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
 pub mod day3;
 pub mod day4;
 pub mod day5;
@@ -21,3 +29,47 @@ pub mod day9;
 
 #[macro_use]
 extern crate lazy_static;
+
+pub use nickwb_advent_solution as solution;
+use solution::Entry;
+
+// Every day, in order
+pub fn solutions() -> Vec<Entry> {
+    vec![
+        Entry::of::<day1::Solver>(),
+        Entry::of::<day2::Solver>(),
+        Entry::of::<day3::Solver>(),
+        Entry::of::<day4::Solver>(),
+        Entry::of::<day5::Solver>(),
+        Entry::of::<day6::Solver>(),
+        Entry::of::<day7::Solver>(),
+        Entry::of::<day8::Solver>(),
+        Entry::of::<day9::Solver>(),
+        Entry::of::<day10::Solver>(),
+        Entry::of::<day11::Solver>(),
+        Entry::of::<day12::Solver>(),
+        Entry::of::<day13::Solver>(),
+        Entry::of::<day14::Solver>(),
+        Entry::of::<day15::Solver>(),
+        Entry::of::<day16::Solver>(),
+        Entry::of::<day17::Solver>(),
+        Entry::of::<day18::Solver>(),
+        Entry::of::<day19::Solver>(),
+        Entry::of::<day20::Solver>(),
+        Entry::of::<day21::Solver>(),
+        Entry::of::<day22::Solver>(),
+        Entry::of::<day23::Solver>(),
+        Entry::of::<day24::Solver>(),
+        Entry::of::<day25::Solver>(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn every_day_is_registered_once() {
+        let days: Vec<u32> = super::solutions().iter().map(|e| e.day).collect();
+        assert_eq!((1..=25).collect::<Vec<u32>>(), days);
+        assert!(super::solutions().iter().all(|e| e.year == 2019));
+    }
+}
@@ -1,9 +1,11 @@
 use std::str::FromStr;
 
 pub fn read_int_array<T: FromStr>(path: &str) -> Vec<T> {
-    std::fs::read_to_string(path)
-        .unwrap()
-        .lines()
+    parse_int_array(&std::fs::read_to_string(path).unwrap())
+}
+
+pub fn parse_int_array<T: FromStr>(text: &str) -> Vec<T> {
+    text.lines()
         .flat_map(|l| l.split(','))
         .map(|v| v.trim())
         .filter(|v| !v.is_empty())
@@ -19,3 +21,8 @@ pub fn read_int_array<T: FromStr>(path: &str) -> Vec<T> {
 pub fn read_file(path: &str) -> String {
     std::fs::read_to_string(path).unwrap()
 }
+
+// Inputs are downloaded per user, so a day's input may not be there yet
+pub fn try_read_file(path: &str) -> Option<String> {
+    std::fs::read_to_string(path).ok()
+}
@@ -1,29 +1,5 @@
-use nickwb_advent_2020::*;
+use nickwb_advent_2020::{solution, solutions};
 
 fn main() {
-    day1::run_day_one();
-    day2::run_day_two();
-    day3::run_day_three();
-    day4::run_day_four();
-    day5::run_day_five();
-    day6::run_day_six();
-    day7::run_day_seven();
-    day8::run_day_eight();
-    day9::run_day_nine();
-    day10::run_day_ten();
-    day11::run_day_eleven();
-    day12::run_day_twelve();
-    day13::run_day_thirteen();
-    day14::run_day_fourteen();
-    day15::run_day_fifteen();
-    day16::run_day_sixteen();
-    day17::run_day_seventeen();
-    day18::run_day_eighteen();
-    day19::run_day_nineteen();
-    day20::run_day_twenty();
-    day21::run_day_twenty_one();
-    day22::run_day_twenty_two();
-    day23::run_day_twenty_three();
-    day24::run_day_twenty_four();
-    day25::run_day_twenty_five();
+    solution::main(solutions());
 }
@@ -1,3 +1,4 @@
+use crate::solution::{self, Skipped, Solution};
 use itertools::Itertools;
 use std::iter::Iterator;
 
@@ -39,9 +40,30 @@ fn inputs() -> Vec<i32> {
 }
 
 pub fn run_day_one() {
-    let inputs = inputs();
-    println!("Day 1, Part 1: {}", calculate_part_1(&inputs));
-    println!("Day 1, Part 2: {}", calculate_part_2(&inputs));
+    solution::run::<Solver>();
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 1;
+
+    type Input = Vec<i32>;
+    type PartOne = i32;
+    type PartTwo = i32;
+
+    fn parse() -> Result<Vec<i32>, Skipped> {
+        Ok(inputs())
+    }
+
+    fn part_one(input: &Vec<i32>) -> i32 {
+        calculate_part_1(input)
+    }
+
+    fn part_two(input: &Vec<i32>) -> i32 {
+        calculate_part_2(input)
+    }
 }
 
 #[cfg(test)]
@@ -1,9 +1,31 @@
+use crate::solution::{self, Skipped, Solution};
 use std::collections::HashMap;
 
 pub fn run_day_ten() {
-    let inputs = inputs();
-    println!("Day 10, Part 1: {}", calculate_part_1(&inputs));
-    println!("Day 10, Part 2: {}", calculate_part_2(&inputs));
+    solution::run::<Solver>();
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 10;
+
+    type Input = Vec<usize>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse() -> Result<Vec<usize>, Skipped> {
+        Ok(inputs())
+    }
+
+    fn part_one(input: &Vec<usize>) -> usize {
+        calculate_part_1(input)
+    }
+
+    fn part_two(input: &Vec<usize>) -> usize {
+        calculate_part_2(input)
+    }
 }
 
 fn inputs() -> Vec<usize> {
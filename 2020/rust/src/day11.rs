@@ -1,9 +1,31 @@
+use crate::solution::{self, Skipped, Solution};
 use rayon::prelude::*;
 
 pub fn run_day_eleven() {
-    let inputs = inputs();
-    println!("Day 11, Part 1: {}", calculate_part_1(inputs.clone()));
-    println!("Day 11, Part 2: {}", calculate_part_2(inputs));
+    solution::run::<Solver>();
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 11;
+
+    type Input = Map;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse() -> Result<Map, Skipped> {
+        Ok(inputs())
+    }
+
+    fn part_one(input: &Map) -> usize {
+        calculate_part_1(input.clone())
+    }
+
+    fn part_two(input: &Map) -> usize {
+        calculate_part_2(input.clone())
+    }
 }
 
 fn calculate_part_1(map: Map) -> usize {
@@ -27,7 +49,7 @@ enum GridCell {
 }
 
 #[derive(Debug, Clone)]
-struct Map {
+pub struct Map {
     width: isize,
     height: isize,
     seats: Vec<Vec<GridCell>>,
@@ -1,13 +1,36 @@
+use crate::solution::{self, Skipped, Solution};
+
 pub fn run_day_twelve() {
-    let inputs = inputs();
-    println!("Day 12, Part 1: {}", calculate_part_1(&inputs));
-    println!("Day 12, Part 2: {}", calculate_part_2(&inputs));
+    solution::run::<Solver>();
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 12;
+
+    type Input = Instructions;
+    type PartOne = isize;
+    type PartTwo = isize;
+
+    fn parse() -> Result<Instructions, Skipped> {
+        Ok(inputs())
+    }
+
+    fn part_one(input: &Instructions) -> isize {
+        calculate_part_1(input)
+    }
+
+    fn part_two(input: &Instructions) -> isize {
+        calculate_part_2(input)
+    }
 }
 
 type Position = (isize, isize);
 type Step = (StepType, isize);
 
-struct Instructions {
+pub struct Instructions {
     steps: Vec<Step>,
 }
 
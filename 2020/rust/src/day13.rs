@@ -1,7 +1,30 @@
+use crate::solution::{self, Skipped, Solution};
+
 pub fn run_day_thirteen() {
-    let inputs = inputs();
-    println!("Day 13, Part 1: {}", calculate_part_1(&inputs));
-    println!("Day 13, Part 2: {}", calculate_part_2(&inputs));
+    solution::run::<Solver>();
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 13;
+
+    type Input = BusInput;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse() -> Result<BusInput, Skipped> {
+        Ok(inputs())
+    }
+
+    fn part_one(input: &BusInput) -> usize {
+        calculate_part_1(input)
+    }
+
+    fn part_two(input: &BusInput) -> usize {
+        calculate_part_2(input)
+    }
 }
 
 fn calculate_part_1(input: &BusInput) -> usize {
@@ -72,7 +95,7 @@ fn calculate_part_2(input: &BusInput) -> usize {
 }
 
 #[derive(Debug)]
-struct BusInput {
+pub struct BusInput {
     earliest_timestamp: usize,
     bus_ids: Vec<BusId>,
 }
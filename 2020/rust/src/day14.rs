@@ -1,10 +1,32 @@
+use crate::solution::{self, Skipped, Solution};
 use regex::Regex;
 use std::collections::HashMap;
 
 pub fn run_day_fourteen() {
-    let inputs = inputs();
-    println!("Day 14, Part 1: {}", calculate_part_1(&inputs));
-    println!("Day 14, Part 2: {}", calculate_part_2(&inputs));
+    solution::run::<Solver>();
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 14;
+
+    type Input = MaskInput;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse() -> Result<MaskInput, Skipped> {
+        Ok(inputs())
+    }
+
+    fn part_one(input: &MaskInput) -> usize {
+        calculate_part_1(input)
+    }
+
+    fn part_two(input: &MaskInput) -> usize {
+        calculate_part_2(input)
+    }
 }
 
 fn calculate_part_1(input: &MaskInput) -> usize {
@@ -80,7 +102,7 @@ fn emulate_returning_sum<F: FnMut(&mut Mem, &Mask, &MemValue) -> ()>(
 }
 
 #[derive(Debug)]
-struct MaskInput {
+pub struct MaskInput {
     instructions: Vec<Instruction>,
 }
 
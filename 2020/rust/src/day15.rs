@@ -1,15 +1,33 @@
+use crate::solution::{self, Skipped, Solution};
+
 pub fn run_day_fifteen() {
-    #[cfg(feature = "slow_problems")]
-    {
-        let inputs = inputs();
-        println!("Day 15, Part 1: {}", calculate_part_1(&inputs));
-        println!("Day 15, Part 2: {}", calculate_part_2(&inputs));
+    solution::run::<Solver>();
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 15;
+
+    type Input = Vec<usize>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse() -> Result<Vec<usize>, Skipped> {
+        #[cfg(feature = "slow_problems")]
+        return Ok(inputs());
+
+        #[cfg(not(feature = "slow_problems"))]
+        Err(Skipped::TooSlow)
+    }
+
+    fn part_one(input: &Vec<usize>) -> usize {
+        calculate_part_1(input)
     }
 
-    #[cfg(not(feature = "slow_problems"))]
-    {
-        println!("Day 15, Part 1: {}", "SKIPPPED");
-        println!("Day 15, Part 2: {}", "SKIPPPED");
+    fn part_two(input: &Vec<usize>) -> usize {
+        calculate_part_2(input)
     }
 }
 
@@ -1,12 +1,34 @@
+use crate::solution::{self, Skipped, Solution};
 use std::collections::HashMap;
 
 use itertools::Itertools;
 use regex::Regex;
 
 pub fn run_day_sixteen() {
-    let inputs = inputs();
-    println!("Day 16, Part 1: {}", calculate_part_1(&inputs));
-    println!("Day 16, Part 2: {}", calculate_part_2(&inputs));
+    solution::run::<Solver>();
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 16;
+
+    type Input = Day16Input;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse() -> Result<Day16Input, Skipped> {
+        Ok(inputs())
+    }
+
+    fn part_one(input: &Day16Input) -> usize {
+        calculate_part_1(input)
+    }
+
+    fn part_two(input: &Day16Input) -> usize {
+        calculate_part_2(input)
+    }
 }
 
 fn calculate_part_1(input: &Day16Input) -> usize {
@@ -101,7 +123,7 @@ fn calculate_part_2(input: &Day16Input) -> usize {
 type Ticket = Vec<usize>;
 
 #[derive(Debug)]
-struct Day16Input {
+pub struct Day16Input {
     rules: Vec<FieldRule>,
     my_ticket: Ticket,
     nearby_tickets: Vec<Ticket>,
@@ -1,10 +1,31 @@
+use crate::solution::{self, Skipped, Solution};
 use std::{collections::HashMap, hash::Hash};
 
 pub fn run_day_seventeen() {
-    let grid_one = inputs();
-    let grid_two = grid_one.clone();
-    println!("Day 17, Part 1: {}", calculate_part_1(grid_one));
-    println!("Day 17, Part 2: {}", calculate_part_2(grid_two));
+    solution::run::<Solver>();
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 17;
+
+    type Input = ActiveGrid;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse() -> Result<ActiveGrid, Skipped> {
+        Ok(inputs())
+    }
+
+    fn part_one(input: &ActiveGrid) -> usize {
+        calculate_part_1(input.clone())
+    }
+
+    fn part_two(input: &ActiveGrid) -> usize {
+        calculate_part_2(input.clone())
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
@@ -16,7 +37,7 @@ struct Point {
 }
 
 #[derive(Debug, Clone)]
-struct ActiveGrid {
+pub struct ActiveGrid {
     cells: Vec<Point>,
 
     // candidates and swap_cells are just collections that we recycle to avoid their re-allocation
@@ -1,3 +1,4 @@
+use crate::solution::{self, Skipped, Solution};
 use nom::{
     branch::alt,
     character::complete::{self, space0},
@@ -8,9 +9,30 @@ use nom::{
 };
 
 pub fn run_day_eighteen() {
-    let inputs = inputs();
-    println!("Day 18, Part 1: {}", calculate_part_1(&inputs));
-    println!("Day 18, Part 2: {}", calculate_part_2(&inputs));
+    solution::run::<Solver>();
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 18;
+
+    type Input = Vec<String>;
+    type PartOne = i64;
+    type PartTwo = i64;
+
+    fn parse() -> Result<Vec<String>, Skipped> {
+        Ok(inputs())
+    }
+
+    fn part_one(input: &Vec<String>) -> i64 {
+        calculate_part_1(input)
+    }
+
+    fn part_two(input: &Vec<String>) -> i64 {
+        calculate_part_2(input)
+    }
 }
 
 pub fn calculate_part_1<T: AsRef<str>>(lines: &[T]) -> i64 {
@@ -1,3 +1,4 @@
+use crate::solution::{self, Skipped, Solution};
 use std::collections::HashMap;
 
 use nom::{
@@ -11,28 +12,49 @@ use nom::{
 };
 
 pub fn run_day_nineteen() {
-    let mut inputs = inputs();
-    println!("Day 19, Part 1: {}", calculate_part_1(&inputs));
-    println!("Day 19, Part 2: {}", calculate_part_2(&mut inputs));
+    solution::run::<Solver>();
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 19;
+
+    type Input = Day19;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse() -> Result<Day19, Skipped> {
+        Ok(inputs())
+    }
+
+    fn part_one(input: &Day19) -> usize {
+        calculate_part_1(input)
+    }
+
+    fn part_two(input: &Day19) -> usize {
+        calculate_part_2(&mut input.clone())
+    }
 }
 
 type RuleNum = i32;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 enum RuleSpec {
     Literal(char),
     Sequence(Vec<RuleNum>),
     Alternation((Vec<RuleNum>, Vec<RuleNum>)),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct MessageRule {
     num: RuleNum,
     spec: RuleSpec,
 }
 
-#[derive(Debug)]
-struct Day19 {
+#[derive(Debug, Clone)]
+pub struct Day19 {
     rules: HashMap<RuleNum, MessageRule>,
     messages: Vec<String>,
 }
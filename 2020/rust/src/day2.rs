@@ -1,3 +1,4 @@
+use crate::solution::{self, Skipped, Solution};
 use regex::Regex;
 
 lazy_static! {
@@ -56,15 +57,30 @@ fn inputs() -> String {
 }
 
 pub fn run_day_two() {
-    let inputs = inputs();
-    println!(
-        "Day 2, Part 1: {}",
-        count_valid_lines(&inputs, validation_rule_one)
-    );
-    println!(
-        "Day 2, Part 2: {}",
-        count_valid_lines(&inputs, validation_rule_two)
-    );
+    solution::run::<Solver>();
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 2;
+
+    type Input = String;
+    type PartOne = i32;
+    type PartTwo = i32;
+
+    fn parse() -> Result<String, Skipped> {
+        Ok(inputs())
+    }
+
+    fn part_one(input: &String) -> i32 {
+        count_valid_lines(input, validation_rule_one)
+    }
+
+    fn part_two(input: &String) -> i32 {
+        count_valid_lines(input, validation_rule_two)
+    }
 }
 
 #[cfg(test)]
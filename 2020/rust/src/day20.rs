@@ -7,22 +7,38 @@ use self::{
     monster::{subtract_monsters, ResolvedImage},
     wave::solve_grid,
 };
+use crate::solution::{self, Skipped, Solution};
 use input::*;
 
 pub fn run_day_twenty() {
-    #[cfg(feature = "slow_problems")]
-    {
-        let inputs = inputs();
-        let (part_1, part_2) = calculate_both_parts(&inputs);
+    solution::run::<Solver>();
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 20;
+
+    // Both parts come out of the same solve, so it's done once up front
+    type Input = (i64, i64);
+    type PartOne = i64;
+    type PartTwo = i64;
+
+    fn parse() -> Result<(i64, i64), Skipped> {
+        #[cfg(feature = "slow_problems")]
+        return Ok(calculate_both_parts(&inputs()));
+
+        #[cfg(not(feature = "slow_problems"))]
+        Err(Skipped::TooSlow)
+    }
 
-        println!("Day 20, Part 1: {}", part_1);
-        println!("Day 20, Part 2: {}", part_2);
+    fn part_one(answers: &(i64, i64)) -> i64 {
+        answers.0
     }
 
-    #[cfg(not(feature = "slow_problems"))]
-    {
-        println!("Day 20, Part 1: SKIPPED");
-        println!("Day 20, Part 2: SKIPPED");
+    fn part_two(answers: &(i64, i64)) -> i64 {
+        answers.1
     }
 }
 
@@ -1,12 +1,34 @@
+use crate::solution::{self, Skipped, Solution};
 use itertools::Itertools;
 use slotmap::{new_key_type, Key, SecondaryMap, SlotMap};
 use std::collections::HashSet;
 
 pub fn run_day_twenty_one() {
-    let inputs = inputs();
-    let (part_1, part_2) = calculate_both_parts(&inputs);
-    println!("Day 21, Part 1: {}", part_1);
-    println!("Day 21, Part 2: {}", part_2);
+    solution::run::<Solver>();
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 21;
+
+    // Both parts come out of the same search, so it's done once up front
+    type Input = (usize, String);
+    type PartOne = usize;
+    type PartTwo = String;
+
+    fn parse() -> Result<(usize, String), Skipped> {
+        Ok(calculate_both_parts(&inputs()))
+    }
+
+    fn part_one(answers: &(usize, String)) -> usize {
+        answers.0
+    }
+
+    fn part_two(answers: &(usize, String)) -> String {
+        answers.1.clone()
+    }
 }
 
 fn calculate_both_parts(inputs: &Inputs) -> (usize, String) {
@@ -144,7 +166,7 @@ new_key_type! { struct Ingredient; }
 new_key_type! { struct Allergen; }
 
 #[derive(Debug)]
-struct Inputs {
+pub struct Inputs {
     ingredient_map: SlotMap<Ingredient, String>,
     allergen_map: SlotMap<Allergen, String>,
     foods: Vec<Food>,
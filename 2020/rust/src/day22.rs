@@ -1,24 +1,47 @@
+use crate::solution::{self, Skipped, Solution};
 use std::collections::{HashSet, VecDeque};
 
 pub fn run_day_twenty_two() {
-    let mut inputs = inputs();
-    let (part_1, part_2) = calculate_both_parts(&mut inputs);
-    println!("Day 22, Part 1: {}", part_1);
-    println!("Day 22, Part 2: {}", part_2);
+    solution::run::<Solver>();
 }
 
-fn calculate_both_parts(inputs: &mut Inputs) -> (usize, usize) {
-    let mut first = inputs.clone();
-    first.play_simple();
-    let part_1 = first.get_score();
+pub struct Solver;
 
-    inputs.play_recursive();
-    let part_2 = inputs.get_score();
-    (part_1, part_2)
+impl Solution for Solver {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 22;
+
+    type Input = Inputs;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse() -> Result<Inputs, Skipped> {
+        Ok(inputs())
+    }
+
+    fn part_one(input: &Inputs) -> usize {
+        play_simple(input)
+    }
+
+    fn part_two(input: &Inputs) -> usize {
+        play_recursive(input)
+    }
+}
+
+fn play_simple(inputs: &Inputs) -> usize {
+    let mut game = inputs.clone();
+    game.play_simple();
+    game.get_score()
+}
+
+fn play_recursive(inputs: &Inputs) -> usize {
+    let mut game = inputs.clone();
+    game.play_recursive();
+    game.get_score()
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-struct Inputs {
+pub struct Inputs {
     player_one: VecDeque<usize>,
     player_two: VecDeque<usize>,
 }
@@ -173,17 +196,15 @@ mod tests {
             10
         ";
 
-        let mut inputs = Inputs::parse(text);
-        let (part_1, part_2) = calculate_both_parts(&mut inputs);
-        assert_eq!(306, part_1);
-        assert_eq!(291, part_2);
+        let inputs = Inputs::parse(text);
+        assert_eq!(306, play_simple(&inputs));
+        assert_eq!(291, play_recursive(&inputs));
     }
 
     #[test]
     #[cfg(feature = "slow_problems")]
     fn actual_inputs() {
-        let mut inputs = inputs();
-        let (part_1, part_2) = calculate_both_parts(&mut inputs);
+        let (part_1, part_2) = solution::answers::<Solver>();
         assert_eq!(35562, part_1);
         assert_eq!(34424, part_2);
     }
@@ -1,17 +1,36 @@
+use crate::solution::{self, Skipped, Solution};
 use std::collections::HashMap;
 
+const INPUT: &str = "853192647";
+
 pub fn run_day_twenty_three() {
-    let input = "853192647";
-    println!("Day 23, Part 1: {}", calculate_part_1(input));
+    solution::run::<Solver>();
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 23;
+
+    type Input = String;
+    type PartOne = String;
+    type PartTwo = String;
 
-    #[cfg(feature = "slow_problems")]
-    {
-        println!("Day 23, Part 2: {}", calculate_part_2(input));
+    fn parse() -> Result<String, Skipped> {
+        Ok(INPUT.to_string())
     }
 
-    #[cfg(not(feature = "slow_problems"))]
-    {
-        println!("Day 23, Part 2: SKIPPED");
+    fn part_one(input: &String) -> String {
+        calculate_part_1(input)
+    }
+
+    fn part_two(_input: &String) -> String {
+        #[cfg(feature = "slow_problems")]
+        return calculate_part_2(_input).to_string();
+
+        #[cfg(not(feature = "slow_problems"))]
+        "SKIPPED".to_string()
     }
 }
 
@@ -1,20 +1,43 @@
+use crate::solution::{self, Skipped, Solution};
 use std::collections::HashMap;
 
 pub fn run_day_twenty_four() {
-    let inputs = inputs();
-    let (part_1, part_2) = calculate_both_parts(&inputs);
-    println!("Day 24, Part 1: {}", part_1);
-    println!("Day 24, Part 2: {}", part_2);
+    solution::run::<Solver>();
 }
 
-fn calculate_both_parts(inputs: &Inputs) -> (usize, usize) {
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 24;
+
+    type Input = Inputs;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse() -> Result<Inputs, Skipped> {
+        Ok(inputs())
+    }
+
+    fn part_one(input: &Inputs) -> usize {
+        count_initial_tiles(input)
+    }
+
+    fn part_two(input: &Inputs) -> usize {
+        count_tiles_after(input, 100)
+    }
+}
+
+fn count_initial_tiles(inputs: &Inputs) -> usize {
+    TiledFloor::from_inputs(inputs).count_black_tiles()
+}
+
+fn count_tiles_after(inputs: &Inputs, days: usize) -> usize {
     let mut floor = TiledFloor::from_inputs(inputs);
-    let part_1 = floor.count_black_tiles();
-    for _i in 0..100 {
+    for _i in 0..days {
         floor.single_day_transform();
     }
-    let part_2 = floor.count_black_tiles();
-    (part_1, part_2)
+    floor.count_black_tiles()
 }
 
 struct TiledFloor {
@@ -112,7 +135,7 @@ enum TileColor {
 }
 
 #[derive(Debug)]
-struct Inputs {
+pub struct Inputs {
     flip_paths: Vec<Path>,
 }
 
@@ -262,15 +285,13 @@ mod tests {
         ";
 
         let inputs = Inputs::parse(text);
-        let (part_1, part_2) = calculate_both_parts(&inputs);
-        assert_eq!(10, part_1);
-        assert_eq!(2208, part_2);
+        assert_eq!(10, count_initial_tiles(&inputs));
+        assert_eq!(2208, count_tiles_after(&inputs, 100));
     }
 
     #[test]
     fn actual_inputs() {
-        let inputs = inputs();
-        let (part_1, part_2) = calculate_both_parts(&inputs);
+        let (part_1, part_2) = solution::answers::<Solver>();
         assert_eq!(500, part_1);
         assert_eq!(4280, part_2);
     }
@@ -1,7 +1,30 @@
+use crate::solution::{self, NoPartTwo, Skipped, Solution};
+
 pub fn run_day_twenty_five() {
-    let inputs = Inputs::new(18356117, 5909654);
-    let part_1 = calculate_part_1(&inputs);
-    println!("Day 25, Part 1: {}", part_1);
+    solution::run::<Solver>();
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 25;
+
+    type Input = Inputs;
+    type PartOne = usize;
+    type PartTwo = NoPartTwo;
+
+    fn parse() -> Result<Inputs, Skipped> {
+        Ok(Inputs::new(18356117, 5909654))
+    }
+
+    fn part_one(input: &Inputs) -> usize {
+        calculate_part_1(input)
+    }
+
+    fn part_two(_: &Inputs) -> NoPartTwo {
+        NoPartTwo
+    }
 }
 
 fn calculate_part_1(inputs: &Inputs) -> usize {
@@ -10,7 +33,7 @@ fn calculate_part_1(inputs: &Inputs) -> usize {
     key
 }
 
-struct Inputs {
+pub struct Inputs {
     card_pub: usize,
     door_pub: usize,
 }
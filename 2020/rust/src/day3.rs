@@ -1,7 +1,8 @@
+use crate::solution::{self, Skipped, Solution};
 use std::collections::HashSet;
 
 #[derive(Debug)]
-struct Map {
+pub struct Map {
     width: usize,
     height: usize,
     trees: HashSet<(usize, usize)>,
@@ -65,10 +66,30 @@ fn calculate_all_paths(map: &Map) -> usize {
 }
 
 pub fn run_day_three() {
-    let inputs = inputs();
-    let map = parse_map(&inputs).unwrap();
-    println!("Day 3, Part 1: {}", calculate_path(&map, &PART_ONE_STEP));
-    println!("Day 3, Part 2: {}", calculate_all_paths(&map));
+    solution::run::<Solver>();
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 3;
+
+    type Input = Map;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse() -> Result<Map, Skipped> {
+        Ok(parse_map(&inputs()).unwrap())
+    }
+
+    fn part_one(input: &Map) -> usize {
+        calculate_path(input, &PART_ONE_STEP)
+    }
+
+    fn part_two(input: &Map) -> usize {
+        calculate_all_paths(input)
+    }
 }
 
 fn inputs() -> String {
@@ -1,16 +1,39 @@
+use crate::solution::{self, Skipped, Solution};
 use std::collections::HashMap;
 
 use regex::Regex;
 
 pub fn run_day_four() {
-    let inputs = inputs();
-    let passports = parse_all_passports(&inputs);
+    solution::run::<Solver>();
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 4;
+
+    type Input = String;
+    type PartOne = usize;
+    type PartTwo = usize;
 
-    let part_1 = passports.iter().filter(|p| p.has_fields()).count();
-    println!("Day 4, Part 1: {}", part_1);
+    fn parse() -> Result<String, Skipped> {
+        Ok(inputs())
+    }
+
+    fn part_one(input: &String) -> usize {
+        parse_all_passports(input)
+            .iter()
+            .filter(|p| p.has_fields())
+            .count()
+    }
 
-    let part_2 = passports.iter().filter(|p| p.is_valid()).count();
-    println!("Day 4, Part 2: {}", part_2);
+    fn part_two(input: &String) -> usize {
+        parse_all_passports(input)
+            .iter()
+            .filter(|p| p.is_valid())
+            .count()
+    }
 }
 
 fn inputs() -> String {
@@ -1,7 +1,30 @@
+use crate::solution::{self, Skipped, Solution};
+
 pub fn run_day_five() {
-    let inputs = inputs();
-    println!("Day 5, Part 1: {}", get_largest_id(&inputs));
-    println!("Day 5, Part 2: {}", find_gap_id(&inputs));
+    solution::run::<Solver>();
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 5;
+
+    type Input = String;
+    type PartOne = u16;
+    type PartTwo = u16;
+
+    fn parse() -> Result<String, Skipped> {
+        Ok(inputs())
+    }
+
+    fn part_one(input: &String) -> u16 {
+        get_largest_id(input)
+    }
+
+    fn part_two(input: &String) -> u16 {
+        find_gap_id(input)
+    }
 }
 
 fn parse_seat_number(text: &str) -> u16 {
@@ -1,10 +1,31 @@
+use crate::solution::{self, Skipped, Solution};
 use std::collections::{HashMap, HashSet};
 
 pub fn run_day_six() {
-    let inputs = inputs();
-    let groups = parse_input(&inputs);
-    println!("Day 6, Part 1: {}", sum_unique_groups(&groups));
-    println!("Day 6, Part 2: {}", sum_matching_groups(&groups));
+    solution::run::<Solver>();
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 6;
+
+    type Input = Vec<TravelGroup>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse() -> Result<Vec<TravelGroup>, Skipped> {
+        Ok(parse_input(&inputs()))
+    }
+
+    fn part_one(input: &Vec<TravelGroup>) -> usize {
+        sum_unique_groups(input)
+    }
+
+    fn part_two(input: &Vec<TravelGroup>) -> usize {
+        sum_matching_groups(input)
+    }
 }
 
 fn inputs() -> String {
@@ -19,7 +40,7 @@ fn sum_matching_groups(groups: &[TravelGroup]) -> usize {
     groups.iter().map(|g| g.count_matching()).sum()
 }
 
-struct TravelGroup {
+pub struct TravelGroup {
     people: Vec<Person>,
 }
 
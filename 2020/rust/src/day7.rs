@@ -1,11 +1,33 @@
+use crate::solution::{self, Skipped, Solution};
 use std::{collections::HashMap, collections::HashSet};
 
 use regex::Regex;
 
 pub fn run_day_seven() {
-    let inputs = inputs();
-    println!("Day 7, Part 1: {}", calculate_part_1(&inputs));
-    println!("Day 7, Part 2: {}", calculate_part_2(&inputs));
+    solution::run::<Solver>();
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 7;
+
+    type Input = InputInterpretation;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse() -> Result<InputInterpretation, Skipped> {
+        Ok(inputs())
+    }
+
+    fn part_one(input: &InputInterpretation) -> usize {
+        calculate_part_1(input)
+    }
+
+    fn part_two(input: &InputInterpretation) -> usize {
+        calculate_part_2(input)
+    }
 }
 
 fn inputs() -> InputInterpretation {
@@ -57,7 +79,7 @@ fn count_children_recursive(input: &InputInterpretation, container_bag: BagId) -
 struct BagId(usize);
 
 #[derive(Debug)]
-struct InputInterpretation {
+pub struct InputInterpretation {
     bag_types: HashMap<String, BagId>,
     rules: Vec<BagRule>,
 }
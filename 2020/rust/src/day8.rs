@@ -1,11 +1,33 @@
+use crate::solution::{self, Skipped, Solution};
 use std::collections::HashSet;
 
 use regex::Regex;
 
 pub fn run_day_eight() {
-    let inputs = inputs();
-    println!("Day 8, Part 1: {}", calculate_part_1(&inputs));
-    println!("Day 8, Part 2: {}", calculate_part_2(&inputs));
+    solution::run::<Solver>();
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 8;
+
+    type Input = Program;
+    type PartOne = isize;
+    type PartTwo = isize;
+
+    fn parse() -> Result<Program, Skipped> {
+        Ok(inputs())
+    }
+
+    fn part_one(input: &Program) -> isize {
+        calculate_part_1(input)
+    }
+
+    fn part_two(input: &Program) -> isize {
+        calculate_part_2(input)
+    }
 }
 
 fn calculate_part_1(program: &Program) -> isize {
@@ -170,7 +192,7 @@ impl<'prog> ModifiedProgram<'prog> {
     }
 }
 
-struct Program {
+pub struct Program {
     instructions: Vec<Instruction>,
 }
 
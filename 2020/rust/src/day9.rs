@@ -1,11 +1,32 @@
+use crate::solution::{self, Skipped, Solution};
 use itertools::{Itertools, MinMaxResult};
 use std::collections::VecDeque;
 
 pub fn run_day_nine() {
-    let inputs = inputs();
-    let part_1 = calculate_part_1(&inputs, 25);
-    println!("Day 9, Part 1: {}", part_1);
-    println!("Day 9, Part 2: {}", calculate_part_2(&inputs, part_1));
+    solution::run::<Solver>();
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    const YEAR: u32 = 2020;
+    const DAY: u32 = 9;
+
+    type Input = Vec<usize>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse() -> Result<Vec<usize>, Skipped> {
+        Ok(inputs())
+    }
+
+    fn part_one(input: &Vec<usize>) -> usize {
+        calculate_part_1(input, 25)
+    }
+
+    fn part_two(input: &Vec<usize>) -> usize {
+        calculate_part_2(input, calculate_part_1(input, 25))
+    }
 }
 
 fn inputs() -> Vec<usize> {
@@ -0,0 +1,179 @@
+// Running the days of a year, shared by every year's crate
+
+use std::fmt;
+
+// A day of the puzzle: reading the input, then answering each part from it
+pub trait Solution {
+    const YEAR: u32;
+    const DAY: u32;
+
+    type Input;
+    type PartOne: fmt::Display;
+    type PartTwo: fmt::Display;
+
+    fn parse() -> Result<Self::Input, Skipped>;
+    fn part_one(input: &Self::Input) -> Self::PartOne;
+    fn part_two(input: &Self::Input) -> Self::PartTwo;
+}
+
+// Why a day couldn't be run
+#[derive(Debug, PartialEq)]
+pub enum Skipped {
+    NoInput(&'static str),
+    TooSlow,
+}
+
+impl fmt::Display for Skipped {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Skipped::NoInput(path) => write!(f, "there is no {}", path),
+            Skipped::TooSlow => write!(f, "it is too slow without the slow_problems feature"),
+        }
+    }
+}
+
+// The answer for days which only have one part
+pub struct NoPartTwo;
+
+impl fmt::Display for NoPartTwo {
+    fn fmt(&self, _: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Ok(())
+    }
+}
+
+pub fn read_input(path: &'static str) -> Result<String, Skipped> {
+    std::fs::read_to_string(path).map_err(|_| Skipped::NoInput(path))
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Answers {
+    pub part_one: String,
+    pub part_two: Option<String>,
+}
+
+pub fn solve<S: Solution>() -> Result<Answers, Skipped> {
+    let input = S::parse()?;
+    let part_one = S::part_one(&input).to_string();
+    let part_two = S::part_two(&input).to_string();
+    Ok(Answers {
+        part_one,
+        part_two: Some(part_two).filter(|a| !a.is_empty()),
+    })
+}
+
+// Both answers, keeping their types, for checking a day in its tests
+pub fn answers<S: Solution>() -> (S::PartOne, S::PartTwo) {
+    let input = S::parse().unwrap_or_else(|skipped| panic!("Skipped, {}", skipped));
+    (S::part_one(&input), S::part_two(&input))
+}
+
+fn print_answer(day: u32, part: u32, answer: &str) {
+    // Pictures start on their own line
+    match answer.contains('\n') {
+        true => println!("Day {}, Part {}:\n{}", day, part, answer.trim_end()),
+        false => println!("Day {}, Part {}: {}", day, part, answer),
+    }
+}
+
+pub fn run<S: Solution>() {
+    Entry::of::<S>().run();
+}
+
+// A day in the registry, without the types that are particular to it
+pub struct Entry {
+    pub year: u32,
+    pub day: u32,
+    solve: fn() -> Result<Answers, Skipped>,
+}
+
+impl Entry {
+    pub fn of<S: Solution>() -> Entry {
+        Entry {
+            year: S::YEAR,
+            day: S::DAY,
+            solve: solve::<S>,
+        }
+    }
+
+    pub fn solve(&self) -> Result<Answers, Skipped> {
+        (self.solve)()
+    }
+
+    pub fn run(&self) {
+        match self.solve() {
+            Ok(answers) => {
+                print_answer(self.day, 1, &answers.part_one);
+                if let Some(part_two) = answers.part_two {
+                    print_answer(self.day, 2, &part_two);
+                }
+            }
+            Err(skipped) => println!("Day {}: Skipped, {}", self.day, skipped),
+        }
+    }
+}
+
+const USAGE: &str = "Usage: runall [list | DAY...]";
+
+// The whole of each year's runall binary: list the days, or run some or all of them
+pub fn main(registry: Vec<Entry>) {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    if args.iter().any(|a| a == "list") {
+        for entry in registry.iter() {
+            println!("{} Day {}", entry.year, entry.day);
+        }
+        return;
+    }
+
+    let days: Vec<u32> = args
+        .iter()
+        .map(|a| a.parse().unwrap_or_else(|_| panic!("{}", USAGE)))
+        .collect();
+
+    for entry in registry.iter() {
+        if days.is_empty() || days.contains(&entry.day) {
+            entry.run();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Example;
+
+    impl Solution for Example {
+        const YEAR: u32 = 2019;
+        const DAY: u32 = 99;
+
+        type Input = Vec<u32>;
+        type PartOne = u32;
+        type PartTwo = NoPartTwo;
+
+        fn parse() -> Result<Self::Input, Skipped> {
+            Ok(vec![1, 2, 3])
+        }
+
+        fn part_one(input: &Self::Input) -> u32 {
+            input.iter().sum()
+        }
+
+        fn part_two(_: &Self::Input) -> NoPartTwo {
+            NoPartTwo
+        }
+    }
+
+    #[test]
+    fn solves_through_the_registry() {
+        let entry = Entry::of::<Example>();
+        assert_eq!((2019, 99), (entry.year, entry.day));
+        assert_eq!(
+            Ok(Answers {
+                part_one: "6".to_string(),
+                part_two: None,
+            }),
+            entry.solve()
+        );
+    }
+}